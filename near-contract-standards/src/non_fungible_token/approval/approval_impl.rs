@@ -3,11 +3,14 @@
 use crate::non_fungible_token::approval::NonFungibleTokenApproval;
 use crate::non_fungible_token::token::TokenId;
 use crate::non_fungible_token::utils::{
-    assert_at_least_one_yocto, bytes_for_approved_account_id, refund_approved_account_ids,
-    refund_approved_account_ids_iter, refund_deposit,
+    bytes_for_approved_account_id, refund_approved_account_ids, refund_approved_account_ids_iter,
+    refund_deposit,
 };
 use crate::non_fungible_token::NonFungibleToken;
-use near_sdk::{assert_one_yocto, env, ext_contract, AccountId, Balance, Gas, Promise};
+use near_sdk::{
+    assert_at_least_one_yocto, assert_one_yocto, env, ext_contract, AccountId, Balance, Gas,
+    Promise,
+};
 
 const GAS_FOR_NFT_APPROVE: Gas = Gas(10_000_000_000_000);
 const NO_DEPOSIT: Balance = 0;
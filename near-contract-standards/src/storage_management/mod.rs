@@ -2,6 +2,9 @@ use near_sdk::json_types::U128;
 use near_sdk::serde::Serialize;
 use near_sdk::AccountId;
 
+pub mod storage_impl;
+pub use storage_impl::StorageManager;
+
 #[derive(Serialize)]
 #[serde(crate = "near_sdk::serde")]
 pub struct StorageBalance {
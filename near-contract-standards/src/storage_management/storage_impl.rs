@@ -0,0 +1,278 @@
+use crate::storage_management::{StorageBalance, StorageBalanceBounds, StorageManagement};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::collections::LookupMap;
+use near_sdk::json_types::U128;
+use near_sdk::{assert_one_yocto, env, log, AccountId, Balance, IntoStorageKey, Promise};
+
+/// Reusable implementation of the storage management standard that keeps the storage deposit of
+/// every registered account.
+///
+/// Registering an account requires a deposit of at least `min_balance`, which stays locked while
+/// the account is registered. Everything deposited above it is available for withdrawal, up to a
+/// total of `max_balance` per account, the rest is refunded. A contract that composes it should
+/// check that the deposit covers the storage it uses.
+///
+/// The manager doesn't know the data the contract keeps for an account, so its `storage_unregister`
+/// only removes accounts with `force` set. A contract that allows unregistering without `force`
+/// implements `storage_unregister` itself, checks that the account can be removed, e.g. it has no
+/// tokens left, and calls [`unregister`](Self::unregister).
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct StorageManager {
+    /// Total storage deposit of every registered account.
+    pub deposits: LookupMap<AccountId, Balance>,
+    /// The deposit required to register an account.
+    pub min_balance: Balance,
+    /// The maximum total deposit of an account, or `None` if it is not limited.
+    pub max_balance: Option<Balance>,
+}
+
+impl StorageManager {
+    pub fn new<S>(prefix: S, min_balance: Balance, max_balance: Option<Balance>) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        if let Some(max_balance) = max_balance {
+            assert!(min_balance <= max_balance, "The minimum balance exceeds the maximum balance");
+        }
+        Self { deposits: LookupMap::new(prefix), min_balance, max_balance }
+    }
+
+    /// Returns whether the account is registered.
+    pub fn is_registered(&self, account_id: &AccountId) -> bool {
+        self.deposits.contains_key(account_id)
+    }
+
+    /// Internal method that panics if the account is not registered.
+    pub fn assert_registered(&self, account_id: &AccountId) {
+        if !self.is_registered(account_id) {
+            env::panic(format!("The account {} is not registered", account_id).as_bytes());
+        }
+    }
+
+    /// Internal method that removes the account and refunds its whole deposit. Returns the refunded
+    /// deposit, or `None` if the account was not registered.
+    pub fn unregister(&mut self, account_id: &AccountId) -> Option<Balance> {
+        match self.deposits.remove(account_id) {
+            Some(total) => {
+                if total > 0 {
+                    Promise::new(account_id.clone()).transfer(total);
+                }
+                Some(total)
+            }
+            None => {
+                log!("The account {} is not registered", account_id);
+                None
+            }
+        }
+    }
+
+    fn internal_storage_balance_of(&self, account_id: &AccountId) -> Option<StorageBalance> {
+        self.deposits.get(account_id).map(|total| StorageBalance {
+            total: total.into(),
+            available: (total - self.min_balance).into(),
+        })
+    }
+}
+
+impl StorageManagement for StorageManager {
+    fn storage_deposit(
+        &mut self,
+        account_id: Option<AccountId>,
+        registration_only: Option<bool>,
+    ) -> StorageBalance {
+        let amount: Balance = env::attached_deposit();
+        let account_id = account_id.unwrap_or_else(env::predecessor_account_id);
+        let registration_only = registration_only.unwrap_or(false);
+        let refund = match self.deposits.get(&account_id) {
+            Some(_) if registration_only => {
+                log!("The account is already registered, refunding the deposit");
+                amount
+            }
+            Some(total) => {
+                let new_total = match self.max_balance {
+                    Some(max_balance) => (total + amount).min(max_balance.max(total)),
+                    None => total + amount,
+                };
+                self.deposits.insert(&account_id, &new_total);
+                total + amount - new_total
+            }
+            None => {
+                if amount < self.min_balance {
+                    env::panic(b"The attached deposit is less than the minimum storage balance");
+                }
+                let new_total = if registration_only {
+                    self.min_balance
+                } else {
+                    self.max_balance.map_or(amount, |max_balance| amount.min(max_balance))
+                };
+                self.deposits.insert(&account_id, &new_total);
+                amount - new_total
+            }
+        };
+        if refund > 0 {
+            Promise::new(env::predecessor_account_id()).transfer(refund);
+        }
+        self.internal_storage_balance_of(&account_id).unwrap()
+    }
+
+    fn storage_withdraw(&mut self, amount: Option<U128>) -> StorageBalance {
+        assert_one_yocto();
+        let predecessor_account_id = env::predecessor_account_id();
+        self.assert_registered(&predecessor_account_id);
+        let total = self.deposits.get(&predecessor_account_id).unwrap();
+        let available = total - self.min_balance;
+        let amount = amount.map_or(available, |amount| amount.0);
+        if amount > available {
+            env::panic(b"The amount is greater than the available storage balance");
+        }
+        if amount > 0 {
+            self.deposits.insert(&predecessor_account_id, &(total - amount));
+            Promise::new(predecessor_account_id.clone()).transfer(amount);
+        }
+        self.internal_storage_balance_of(&predecessor_account_id).unwrap()
+    }
+
+    /// Unregisters the predecessor account and refunds its whole deposit. Panics unless `force` is
+    /// `true`, see [`StorageManager`].
+    fn storage_unregister(&mut self, force: Option<bool>) -> bool {
+        assert_one_yocto();
+        if force != Some(true) {
+            env::panic(b"Can't unregister the account without force")
+        }
+        self.unregister(&env::predecessor_account_id()).is_some()
+    }
+
+    fn storage_balance_bounds(&self) -> StorageBalanceBounds {
+        StorageBalanceBounds {
+            min: self.min_balance.into(),
+            max: self.max_balance.map(|max_balance| max_balance.into()),
+        }
+    }
+
+    fn storage_balance_of(&self, account_id: AccountId) -> Option<StorageBalance> {
+        self.internal_storage_balance_of(&account_id)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use near_sdk::test_utils::{accounts, get_created_receipts, VMContextBuilder};
+    use near_sdk::testing_env;
+
+    fn deposit(account_id: AccountId, amount: Balance) {
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(account_id)
+            .attached_deposit(amount));
+    }
+
+    fn balance(storage_balance: StorageBalance) -> (Balance, Balance) {
+        (storage_balance.total.0, storage_balance.available.0)
+    }
+
+    #[test]
+    fn test_deposit() {
+        deposit(accounts(0), 100);
+        let mut storage = StorageManager::new(b"s", 100, None);
+        assert_eq!(balance(storage.storage_deposit(None, None)), (100, 0));
+        assert!(get_created_receipts().is_empty());
+        assert!(storage.is_registered(&accounts(0)));
+
+        // Deposit for another account, above the minimum.
+        deposit(accounts(0), 150);
+        assert_eq!(balance(storage.storage_deposit(Some(accounts(1)), None)), (150, 50));
+        deposit(accounts(1), 30);
+        assert_eq!(balance(storage.storage_deposit(None, None)), (180, 80));
+        assert_eq!(balance(storage.storage_balance_of(accounts(1)).unwrap()), (180, 80));
+        assert!(storage.storage_balance_of(accounts(2)).is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "The attached deposit is less than the minimum storage balance")]
+    fn test_deposit_too_small() {
+        deposit(accounts(0), 99);
+        StorageManager::new(b"s", 100, None).storage_deposit(None, None);
+    }
+
+    #[test]
+    fn test_over_deposit_refund() {
+        deposit(accounts(0), 250);
+        let mut storage = StorageManager::new(b"s", 100, Some(200));
+        assert_eq!(balance(storage.storage_deposit(None, None)), (200, 100));
+        assert_eq!(get_created_receipts().len(), 1);
+
+        // Registration only deposits the minimum.
+        deposit(accounts(1), 250);
+        assert_eq!(balance(storage.storage_deposit(None, Some(true))), (100, 0));
+        assert_eq!(get_created_receipts().len(), 1);
+
+        // Registration only refunds everything if already registered.
+        deposit(accounts(1), 50);
+        assert_eq!(balance(storage.storage_deposit(None, Some(true))), (100, 0));
+        assert_eq!(get_created_receipts().len(), 1);
+
+        // A deposit can't exceed the maximum.
+        deposit(accounts(1), 150);
+        assert_eq!(balance(storage.storage_deposit(None, None)), (200, 100));
+        assert_eq!(get_created_receipts().len(), 1);
+        deposit(accounts(1), 10);
+        assert_eq!(balance(storage.storage_deposit(None, None)), (200, 100));
+    }
+
+    #[test]
+    fn test_withdraw() {
+        deposit(accounts(0), 300);
+        let mut storage = StorageManager::new(b"s", 100, None);
+        storage.storage_deposit(None, None);
+
+        deposit(accounts(0), 1);
+        assert_eq!(balance(storage.storage_withdraw(Some(U128(50)))), (250, 150));
+        assert_eq!(get_created_receipts().len(), 1);
+        assert_eq!(balance(storage.storage_withdraw(None)), (100, 0));
+        assert_eq!(balance(storage.storage_withdraw(None)), (100, 0));
+
+        assert!(storage.storage_unregister(Some(true)));
+        assert!(!storage.is_registered(&accounts(0)));
+        assert_eq!(get_created_receipts().len(), 3);
+        assert!(!storage.storage_unregister(Some(true)));
+    }
+
+    #[test]
+    #[should_panic(expected = "Can't unregister the account without force")]
+    fn test_unregister_without_force() {
+        deposit(accounts(0), 100);
+        let mut storage = StorageManager::new(b"s", 100, None);
+        storage.storage_deposit(None, None);
+        deposit(accounts(0), 1);
+        storage.storage_unregister(Some(false));
+    }
+
+    #[test]
+    fn test_unregister() {
+        deposit(accounts(0), 150);
+        let mut storage = StorageManager::new(b"s", 100, None);
+        storage.storage_deposit(Some(accounts(1)), None);
+        assert_eq!(storage.unregister(&accounts(1)), Some(150));
+        assert!(!storage.is_registered(&accounts(1)));
+        assert_eq!(get_created_receipts().len(), 1);
+        assert_eq!(storage.unregister(&accounts(1)), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "The amount is greater than the available storage balance")]
+    fn test_withdraw_too_much() {
+        deposit(accounts(0), 150);
+        let mut storage = StorageManager::new(b"s", 100, None);
+        storage.storage_deposit(None, None);
+        deposit(accounts(0), 1);
+        storage.storage_withdraw(Some(U128(51)));
+    }
+
+    #[test]
+    #[should_panic(expected = "The account bob is not registered")]
+    fn test_withdraw_not_registered() {
+        deposit(accounts(1), 1);
+        StorageManager::new(b"s", 100, None).storage_withdraw(None);
+    }
+}
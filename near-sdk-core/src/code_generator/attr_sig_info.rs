@@ -30,7 +30,8 @@ impl AttrSigInfo {
         );
         let attribute = match input_struct_type {
             InputStructType::Serialization => match &self.input_serializer {
-                SerializerType::JSON => quote! {
+                // Callers send the arguments of `auto` methods as JSON.
+                SerializerType::JSON | SerializerType::Auto => quote! {
                     #[derive(near_sdk::serde::Serialize)]
                     #[serde(crate = "near_sdk::serde")]
                 },
@@ -50,12 +51,17 @@ impl AttrSigInfo {
                         #[derive(near_sdk::borsh::BorshDeserialize)]
                     }
                 }
+                SerializerType::Auto => quote! {
+                    #[derive(near_sdk::serde::Deserialize, near_sdk::borsh::BorshDeserialize)]
+                    #[serde(crate = "near_sdk::serde")]
+                },
             },
         };
         let mut fields = TokenStream2::new();
         for arg in args {
-            let ArgInfo { ty, ident, .. } = &arg;
+            let ArgInfo { ty, ident, serde_attrs, .. } = &arg;
             fields.extend(quote! {
+                #(#serde_attrs)*
                 #ident: #ty,
             });
         }
@@ -183,6 +189,7 @@ impl AttrSigInfo {
                     SerializerType::Borsh => quote! {
                         near_sdk::borsh::BorshDeserialize::try_from_slice(&data).expect("Failed to deserialize callback using Borsh")
                     },
+                    SerializerType::Auto => unreachable!("`auto` is rejected on arguments"),
                 };
                 quote! {
                 #acc
@@ -207,6 +214,7 @@ impl AttrSigInfo {
                     SerializerType::Borsh => quote! {
                         near_sdk::borsh::BorshDeserialize::try_from_slice(&data).expect("Failed to deserialize callback using Borsh")
                     },
+                    SerializerType::Auto => unreachable!("`auto` is rejected on arguments"),
                 };
                 quote! {
                 #acc
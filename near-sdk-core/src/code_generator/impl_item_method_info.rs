@@ -31,6 +31,12 @@ impl ImplItemMethodInfo {
                         &near_sdk::env::input().expect("Expected input since method has arguments.")
                     ).expect("Failed to deserialize input from Borsh.")
                 },
+                // The input is parsed before the state is read, so a failed attempt has nothing
+                // to roll back.
+                SerializerType::Auto => quote! {
+                    near_sdk::env::parse_input_json_or_borsh()
+                        .expect("Failed to deserialize input from JSON or Borsh.")
+                },
             };
             arg_parsing = quote! {
                 let #decomposition : Input = #serializer_invocation ;
@@ -56,7 +62,8 @@ impl ImplItemMethodInfo {
             ..
         } = attr_signature_info;
         let deposit_check = if *is_payable || matches!(method_type, &MethodType::View) {
-            // No check if the method is payable or a view method
+            // No check if the method is payable or a view method. View methods can't be payable and
+            // the attached deposit is not available in view calls, so the check would always fail.
             quote! {}
         } else {
             // If method is not payable, do a check to make sure that it doesn't consume deposit
@@ -70,7 +77,7 @@ impl ImplItemMethodInfo {
         let is_private_check = if *is_private {
             let error = format!("Method {} is private", ident.to_string());
             quote! {
-                if env::current_account_id() != env::predecessor_account_id() {
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
                     near_sdk::env::panic(#error.as_bytes());
                 }
             }
@@ -130,12 +137,23 @@ impl ImplItemMethodInfo {
                         SerializerType::Borsh => quote! {
                             let result = near_sdk::borsh::BorshSerialize::try_to_vec(&result).expect("Failed to serialize the return value using Borsh.");
                         },
+                        SerializerType::Auto => unreachable!("`auto` is rejected for results"),
+                    };
+                    let return_size = if cfg!(feature = "debug-return-size") {
+                        let ident_str = ident.to_string();
+                        quote! {
+                            #[cfg(debug_assertions)]
+                            near_sdk::record_return_size(#ident_str, result.len());
+                        }
+                    } else {
+                        TokenStream2::new()
                     };
                     quote! {
                     #contract_deser
                     let result = #method_invocation;
                     #value_ser
                     near_sdk::env::value_return(&result);
+                    #return_size
                     #contract_ser
                     }
                 }
@@ -175,7 +193,17 @@ impl ImplItemMethodInfo {
                     &attr_signature_info,
                     &attr_signature_info.input_serializer,
                 ),
-                SerializerType::JSON => json_serialize(&attr_signature_info),
+                // `json!` ignores serde attributes like `#[serde(flatten)]`, so the arguments
+                // that have them are serialized through the input struct.
+                SerializerType::JSON | SerializerType::Auto
+                    if attr_signature_info.input_args().any(|arg| !arg.serde_attrs.is_empty()) =>
+                {
+                    crate::TraitItemMethodInfo::generate_serialier(
+                        attr_signature_info,
+                        &attr_signature_info.input_serializer,
+                    )
+                }
+                SerializerType::JSON | SerializerType::Auto => json_serialize(&attr_signature_info),
             }
         } else {
             quote! {
@@ -1,6 +1,7 @@
 use crate::ItemImplInfo;
+use quote::quote;
 use syn::export::TokenStream2;
-use syn::Ident;
+use syn::{parse_quote, Ident, ImplItem, ItemImpl, ReturnType, Type};
 
 impl ItemImplInfo {
     /// Generate the code that wraps
@@ -14,6 +15,39 @@ impl ItemImplInfo {
         res
     }
 
+    /// Wraps the bodies of the exported methods with `near_sdk::with_method_context` so that
+    /// panics are reported together with the name of the method. Methods that return references
+    /// or `impl Trait` are left as is, since their return type can't be written for a closure.
+    pub fn add_method_context(&self, original: &mut ItemImpl) {
+        for subitem in &mut original.items {
+            let method = match subitem {
+                ImplItem::Method(method) => method,
+                _ => continue,
+            };
+            let is_exported = self.methods.iter().any(|info| {
+                info.attr_signature_info.ident == method.sig.ident
+                    && (info.is_public || self.is_trait_impl)
+            });
+            if !is_exported {
+                continue;
+            }
+            let returns = match &method.sig.output {
+                ReturnType::Default => quote! { () },
+                ReturnType::Type(_, ty) => match ty.as_ref() {
+                    Type::Reference(_) | Type::ImplTrait(_) => continue,
+                    ty => quote! { #ty },
+                },
+            };
+            let ident_str = method.sig.ident.to_string();
+            let block = &method.block;
+            method.block = parse_quote! {
+                {
+                    near_sdk::with_method_context(#ident_str, move || -> #returns #block)
+                }
+            };
+        }
+    }
+
     #[cfg(target_arch = "wasm32")]
     pub fn marshall_code(&self) -> TokenStream2 {
         quote! {}
@@ -21,7 +55,7 @@ impl ItemImplInfo {
 
     #[cfg(not(target_arch = "wasm32"))]
     pub fn marshall_code(&self) -> TokenStream2 {
-        use quote::{format_ident, quote, ToTokens};
+        use quote::{format_ident, ToTokens};
         let orig_name = self.ty.clone().into_token_stream();
         let mut name = quote! {Contract};
         if let Ok(input) = syn::parse::<Ident>(orig_name.into()) {
@@ -45,9 +79,37 @@ impl ItemImplInfo {
 #[rustfmt::skip]
 #[cfg(test)]
 mod tests {
-    use syn::{Type, ImplItemMethod, parse_quote};
-    use quote::quote;
+    use syn::{Type, ImplItemMethod, ItemImpl, parse_quote};
+    use quote::{quote, ToTokens};
     use crate::info_extractor::ImplItemMethodInfo;
+    use crate::ItemImplInfo;
+
+    #[test]
+    fn method_context() {
+        let mut item: ItemImpl = parse_quote! {
+            impl Hello {
+                pub fn set(&mut self, value: u64) { self.value = value; }
+                pub fn get(&self) -> u64 { self.value }
+                pub fn get_ref(&self) -> &u64 { &self.value }
+                fn internal(&self) { }
+            }
+        };
+        let info = ItemImplInfo::new(&mut item).unwrap();
+        info.add_method_context(&mut item);
+        let expected: ItemImpl = parse_quote!(
+            impl Hello {
+                pub fn set(&mut self, value: u64) {
+                    near_sdk::with_method_context("set", move || -> () { self.value = value; })
+                }
+                pub fn get(&self) -> u64 {
+                    near_sdk::with_method_context("get", move || -> u64 { self.value })
+                }
+                pub fn get_ref(&self) -> &u64 { &self.value }
+                fn internal(&self) { }
+            }
+        );
+        assert_eq!(expected.to_token_stream().to_string(), item.to_token_stream().to_string());
+    }
 
 
     #[test]
@@ -135,6 +197,73 @@ mod tests {
         assert_eq!(expected.to_string(), actual.to_string());
     }
 
+    #[test]
+    fn arg_serde_default() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self, a: u8, #[serde(default)] b: u8) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                #[derive(near_sdk :: serde :: Deserialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    a: u8,
+                    #[serde(default)]
+                    b: u8,
+                }
+                let Input { a, b, }: Input = near_sdk::serde_json::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .expect("Failed to deserialize input from JSON.");
+                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                contract.method(a, b, );
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+        // The attribute is removed from the original method.
+        assert_eq!(quote!(#method).to_string(), quote!(pub fn method(&self, a: u8, b: u8) { }).to_string());
+    }
+
+    #[test]
+    fn arg_serde_flatten() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self, #[serde(flatten)] args: Args) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                #[derive(near_sdk :: serde :: Deserialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    #[serde(flatten)]
+                    args: Args,
+                }
+                let Input { args, }: Input = near_sdk::serde_json::from_slice(
+                    &near_sdk::env::input().expect("Expected input since method has arguments.")
+                )
+                .expect("Failed to deserialize input from JSON.");
+                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                contract.method(args, );
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn arg_serde_borsh() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self, #[serializer(borsh)] #[serde(default)] b: u8) { }").unwrap();
+        let err = ImplItemMethodInfo::new(&mut method, impl_type).err().unwrap();
+        assert_eq!(err.to_string(), "Serde attributes are only supported for arguments serialized with JSON.");
+    }
+
     #[test]
     fn args_no_return_mut() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -226,6 +355,31 @@ mod tests {
         assert_eq!(expected.to_string(), actual.to_string());
     }
 
+    #[cfg(feature = "debug-return-size")]
+    #[test]
+    fn args_return_ref_return_size() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod =
+            syn::parse_str("pub fn method(&self) -> &Option<u64> { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let result = contract.method();
+                let result =
+                    near_sdk::serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
+                near_sdk::env::value_return(&result);
+                #[cfg(debug_assertions)]
+                near_sdk::record_return_size("method", result.len());
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
     #[test]
     fn arg_ref() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -294,7 +448,7 @@ mod tests {
             #[no_mangle]
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
-                if env::current_account_id() != env::predecessor_account_id() {
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
                     near_sdk::env::panic("Method method is private".as_bytes());
                 }
                 #[derive(near_sdk :: serde :: Deserialize)]
@@ -338,7 +492,7 @@ mod tests {
             #[no_mangle]
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
-                if env::current_account_id() != env::predecessor_account_id() {
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
                     near_sdk::env::panic("Method method is private".as_bytes());
                 }
                 let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
@@ -361,6 +515,38 @@ mod tests {
         assert_eq!(expected.to_string(), actual.to_string());
     }
 
+    #[test]
+    fn callback_arg_return() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = parse_quote! {
+            #[private] pub fn method(&self, #[callback] x: u64) -> u64 { }
+        };
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
+                    near_sdk::env::panic("Method method is private".as_bytes());
+                }
+                let data: Vec<u8> = match near_sdk::env::promise_result(0u64) {
+                    near_sdk::PromiseResult::Successful(x) => x,
+                    _ => panic!("Callback computation {} was not successful", 0u64)
+                };
+                let x: u64 =
+                    near_sdk::serde_json::from_slice(&data).expect("Failed to deserialize callback using JSON");
+                let contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                let result = contract.method(x, );
+                let result =
+                    near_sdk::serde_json::to_vec(&result).expect("Failed to serialize the return value using JSON.");
+                near_sdk::env::value_return(&result);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
     #[test]
     fn callback_args_vec() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -374,7 +560,7 @@ mod tests {
             #[no_mangle]
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
-                if env::current_account_id() != env::predecessor_account_id() {
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
                     near_sdk::env::panic("Method method is private".as_bytes());
                 }
                 #[derive(near_sdk :: serde :: Deserialize)]
@@ -555,7 +741,7 @@ mod tests {
             #[no_mangle]
             pub extern "C" fn method() {
                 near_sdk::env::setup_panic_hook();
-                if env::current_account_id() != env::predecessor_account_id() {
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
                     near_sdk::env::panic("Method method is private".as_bytes());
                 }
                 #[derive(near_sdk :: borsh :: BorshDeserialize)]
@@ -615,7 +801,7 @@ mod tests {
             #[no_mangle]
             pub extern "C" fn private_method() {
                 near_sdk::env::setup_panic_hook();
-                if env::current_account_id() != env::predecessor_account_id() {
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
                     near_sdk::env::panic("Method private_method is private".as_bytes());
                 }
                 if near_sdk::env::attached_deposit() != 0 {
@@ -629,6 +815,158 @@ mod tests {
         assert_eq!(expected.to_string(), actual.to_string());
     }
 
+    #[test]
+    fn private_payable_method() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[private] #[payable] pub fn private_method(&mut self) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn private_method() {
+                near_sdk::env::setup_panic_hook();
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
+                    near_sdk::env::panic("Method private_method is private".as_bytes());
+                }
+                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                contract.private_method();
+                near_sdk::env::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn private_init() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[init] #[private] pub fn new() -> Self { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn new() {
+                near_sdk::env::setup_panic_hook();
+                if near_sdk::env::current_account_id() != near_sdk::env::predecessor_account_id() {
+                    near_sdk::env::panic("Method new is private".as_bytes());
+                }
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic("Method new doesn't accept deposit".as_bytes());
+                }
+                if near_sdk::env::state_exists() {
+                    near_sdk::env::panic(b"The contract has already been initialized");
+                }
+                let contract = Hello::new();
+                near_sdk::env::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn max_gas_method() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[near_bindgen(max_gas = 5_000_000)] pub fn method(&mut self) { self.value += 1; }").unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let expected = quote!(
+            pub fn method(&mut self) {
+                #[cfg(debug_assertions)]
+                let __near_max_gas_guard = near_sdk::MaxGasGuard::new("method", near_sdk::Gas(5_000_000));
+                self.value += 1;
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+    }
+
+    #[test]
+    fn max_gas_invalid() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[near_bindgen(max_gas = -1)] pub fn method(&mut self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
+    #[test]
+    fn auto_serializer_arg() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[serializer(auto)] pub fn method(&mut self, k: u64) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.method_wrapper();
+        let expected = quote!(
+            #[cfg(target_arch = "wasm32")]
+            #[no_mangle]
+            pub extern "C" fn method() {
+                near_sdk::env::setup_panic_hook();
+                if near_sdk::env::attached_deposit() != 0 {
+                    near_sdk::env::panic("Method method doesn't accept deposit".as_bytes());
+                }
+                #[derive(near_sdk::serde::Deserialize, near_sdk::borsh::BorshDeserialize)]
+                #[serde(crate = "near_sdk::serde")]
+                struct Input {
+                    k: u64,
+                }
+                let Input { k, }: Input = near_sdk::env::parse_input_json_or_borsh()
+                    .expect("Failed to deserialize input from JSON or Borsh.");
+                let mut contract: Hello = near_sdk::env::state_read().unwrap_or_default();
+                contract.method(k, );
+                near_sdk::env::state_write(&contract);
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn auto_serializer_invalid() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        for method in &[
+            "#[serializer(borsh)] pub fn method(&mut self, k: u64) { }",
+            "pub fn method(&mut self, #[serializer(auto)] k: u64) { }",
+            "#[serializer(auto)] pub fn method(&mut self, #[serializer(borsh)] k: u64) { }",
+            "#[result_serializer(auto)] pub fn method(&self) -> u64 { 1 }",
+        ] {
+            let mut method: ImplItemMethod = syn::parse_str(method).unwrap();
+            assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        }
+    }
+
+    #[test]
+    fn deprecated_method() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[deprecated_method] pub fn method(&mut self) { self.value += 1; }").unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let expected = quote!(
+            pub fn method(&mut self) {
+                near_sdk::env::log_str("Method method is deprecated");
+                self.value += 1;
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+    }
+
+    #[test]
+    fn deprecated_method_with_replacement() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[deprecated_method(use = \"new_method\")] #[near_bindgen(max_gas = 5)] pub fn method(&mut self) { }").unwrap();
+        ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let expected = quote!(
+            pub fn method(&mut self) {
+                #[cfg(debug_assertions)]
+                let __near_max_gas_guard = near_sdk::MaxGasGuard::new("method", near_sdk::Gas(5));
+                near_sdk::env::log_str("Method method is deprecated, use new_method");
+            }
+        );
+        assert_eq!(expected.to_string(), quote!(#method).to_string());
+    }
+
+    #[test]
+    fn deprecated_method_invalid() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("#[deprecated_method(instead = \"x\")] pub fn method(&mut self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type.clone()).is_err());
+        let mut method: ImplItemMethod = syn::parse_str("#[deprecated_method(use = \"x\", y)] pub fn method(&mut self) { }").unwrap();
+        assert!(ImplItemMethodInfo::new(&mut method, impl_type).is_err());
+    }
+
     #[test]
     fn marshall_one_arg() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
@@ -647,6 +985,30 @@ mod tests {
         assert_eq!(expected.to_string(), actual.to_string());
     }
 
+    #[test]
+    fn marshall_serde_flatten() {
+        let impl_type: Type = syn::parse_str("Hello").unwrap();
+        let mut method: ImplItemMethod = syn::parse_str("pub fn method(&self, #[serde(flatten)] args: Args) { }").unwrap();
+        let method_info = ImplItemMethodInfo::new(&mut method, impl_type).unwrap();
+        let actual = method_info.marshal_method();
+        let expected = quote!(
+                #[cfg(not(target_arch = "wasm32"))]
+                pub fn method(&self, args: Args,) -> near_sdk::PendingContractTx {
+                  #[derive(near_sdk :: serde :: Serialize)]
+                  #[serde(crate = "near_sdk::serde")]
+                  struct Input {
+                      #[serde(flatten)]
+                      args: Args,
+                  }
+                  let args = Input { args, };
+                  let args = near_sdk::serde_json::to_vec(&args)
+                      .expect("Failed to serialize the cross contract args using JSON.");
+                  near_sdk::PendingContractTx::new_from_bytes(self.account_id.clone(), "method", args, true)
+                }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
     #[test]
     fn marshall_borsh() {
         let impl_type: Type = syn::parse_str("Hello").unwrap();
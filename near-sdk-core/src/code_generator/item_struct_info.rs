@@ -1,9 +1,50 @@
-use syn::ItemStruct;
+use syn::{Error, Expr, ExprLit, ItemEnum, ItemStruct, Lit};
 
 #[cfg(not(target_arch = "wasm"))]
 pub fn generate_proxy_struct(input: &ItemStruct) -> proc_macro2::TokenStream {
+    generate_proxy(&input.ident)
+}
+
+#[cfg(target_arch = "wasm")]
+pub fn generate_proxy_struct(input: &ItemStruct) {
+    quote! {}
+}
+
+/// Generates the same proxy as [`generate_proxy_struct`] for a contract whose state is an enum.
+///
+/// Borsh serializes a variant by its position in the enum and ignores explicit discriminants, so
+/// an explicit discriminant that differs from the position of its variant is rejected: it
+/// suggests that the variants can be reordered without breaking the stored state.
+pub fn generate_proxy_enum(input: &ItemEnum) -> syn::Result<proc_macro2::TokenStream> {
+    for (position, variant) in input.variants.iter().enumerate() {
+        if let Some((_, discriminant)) = &variant.discriminant {
+            let value = match discriminant {
+                Expr::Lit(ExprLit { lit: Lit::Int(value), .. }) => value.base10_parse::<usize>()?,
+                _ => {
+                    return Err(Error::new_spanned(
+                        discriminant,
+                        "Discriminants of the contract state must be integer literals.",
+                    ))
+                }
+            };
+            if value != position {
+                return Err(Error::new_spanned(
+                    discriminant,
+                    format!(
+                        "Borsh serializes the variant `{}` by its position {}, not by its \
+                         discriminant. Keep the variants in their original order and add new \
+                         ones at the end, so the stored state stays readable after an upgrade.",
+                        variant.ident, position
+                    ),
+                ));
+            }
+        }
+    }
+    Ok(generate_proxy(&input.ident))
+}
+
+fn generate_proxy(ident: &syn::Ident) -> proc_macro2::TokenStream {
     use quote::{format_ident, quote};
-    let ident = &input.ident;
     let new_name = format_ident!("{}Contract", ident);
     let name = quote! {#new_name};
     quote! {
@@ -13,7 +54,115 @@ pub fn generate_proxy_struct(input: &ItemStruct) -> proc_macro2::TokenStream {
     }
 }
 
-#[cfg(target_arch = "wasm")]
-pub fn generate_proxy_struct(input: &ItemStruct) {
-    quote! {}
+/// Parses the arguments of `#[near_bindgen]` on the contract state, returns `true` for
+/// `#[near_bindgen(schema)]`, which asks for [`generate_state_schema`].
+pub fn parse_state_args(args: proc_macro2::TokenStream) -> syn::Result<bool> {
+    if args.is_empty() {
+        return Ok(false);
+    }
+    let ident: syn::Ident = syn::parse2(args.clone()).map_err(|_| unsupported_state_args(args))?;
+    if ident == "schema" {
+        Ok(true)
+    } else {
+        Err(unsupported_state_args(ident))
+    }
+}
+
+fn unsupported_state_args<T: quote::ToTokens>(tokens: T) -> Error {
+    Error::new_spanned(
+        tokens,
+        "Unsupported argument of `near_bindgen` on the contract state, only `schema` is supported.",
+    )
+}
+
+/// Generates the `__state_borsh_schema` function that returns the Borsh serialized
+/// `BorshSchemaContainer` of the contract state, and exports it from the contract. Upgrade tooling
+/// can compare the schemas of two versions of the contract to check that the new version can
+/// still read the stored state. The state has to derive `BorshSchema`.
+pub fn generate_state_schema(ident: &syn::Ident) -> proc_macro2::TokenStream {
+    use quote::quote;
+    quote! {
+        #[doc(hidden)]
+        pub fn __state_borsh_schema() -> Vec<u8> {
+            near_sdk::borsh::BorshSerialize::try_to_vec(
+                &<#ident as near_sdk::borsh::BorshSchema>::schema_container(),
+            )
+            .expect("Failed to serialize the state schema.")
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        #[export_name = "__state_borsh_schema"]
+        pub extern "C" fn __state_borsh_schema_export() {
+            near_sdk::env::value_return(&__state_borsh_schema());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn enum_proxy() {
+        let input: ItemEnum = parse_quote! {
+            enum State { Active { value: u32 }, Paused }
+        };
+        let actual = generate_proxy_enum(&input).unwrap();
+        let expected = quote::quote! {
+            pub struct StateContract {
+                pub account_id: near_sdk::AccountId,
+            }
+        };
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn state_schema() {
+        let actual =
+            generate_state_schema(&syn::Ident::new("Counter", proc_macro2::Span::call_site()));
+        let expected = quote::quote! {
+            #[doc(hidden)]
+            pub fn __state_borsh_schema() -> Vec<u8> {
+                near_sdk::borsh::BorshSerialize::try_to_vec(
+                    &<Counter as near_sdk::borsh::BorshSchema>::schema_container(),
+                )
+                .expect("Failed to serialize the state schema.")
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            #[export_name = "__state_borsh_schema"]
+            pub extern "C" fn __state_borsh_schema_export() {
+                near_sdk::env::value_return(&__state_borsh_schema());
+            }
+        };
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn state_args() {
+        assert!(!parse_state_args(quote::quote! {}).unwrap());
+        assert!(parse_state_args(quote::quote! { schema }).unwrap());
+        assert!(parse_state_args(quote::quote! { abi }).is_err());
+        assert!(parse_state_args(quote::quote! { schema = true }).is_err());
+    }
+
+    #[test]
+    fn enum_discriminants() {
+        let input: ItemEnum = parse_quote! {
+            enum State { A = 0, B = 1, C }
+        };
+        assert!(generate_proxy_enum(&input).is_ok());
+
+        let input: ItemEnum = parse_quote! {
+            enum State { B = 1, A = 0 }
+        };
+        let err = generate_proxy_enum(&input).unwrap_err();
+        assert!(err.to_string().starts_with("Borsh serializes the variant `B` by its position 0"));
+
+        let input: ItemEnum = parse_quote! {
+            enum State { A = FIRST }
+        };
+        assert!(generate_proxy_enum(&input).is_err());
+    }
 }
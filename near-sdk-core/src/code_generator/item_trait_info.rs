@@ -1,5 +1,5 @@
 use crate::info_extractor::ItemTraitInfo;
-use quote::quote;
+use quote::{format_ident, quote};
 use syn::export::TokenStream2;
 
 impl ItemTraitInfo {
@@ -9,6 +9,7 @@ impl ItemTraitInfo {
         for method in &self.methods {
             result.extend(method.method_wrapper());
         }
+        let builder = self.call_builder();
         let mod_name = &self.mod_name;
         quote! {
            pub mod #mod_name {
@@ -16,6 +17,49 @@ impl ItemTraitInfo {
                 use near_sdk::{Gas, Balance, AccountId, Promise};
                 use std::string::ToString;
                 #result
+                #builder
+            }
+        }
+    }
+
+    /// Generate the `ext` function and the builder it returns, e.g. for trait `MyContract`:
+    /// ```ignore
+    /// my_contract::ext(account_id, gas).with_attached_deposit(1).method(args)
+    /// ```
+    fn call_builder(&self) -> TokenStream2 {
+        let mut methods = TokenStream2::new();
+        for method in &self.methods {
+            methods.extend(method.builder_method());
+        }
+        let builder_ident = format_ident!("{}Ext", self.original.ident);
+        let doc = format!(
+            " Builder of calls to the contract that implements `{}`, created with [`ext`].",
+            self.original.ident
+        );
+        quote! {
+            #[doc = #doc]
+            #[must_use]
+            pub struct #builder_ident {
+                account_id: near_sdk::AccountId,
+                deposit: near_sdk::Balance,
+                static_gas: near_sdk::Gas,
+            }
+
+            /// Starts building a call to `account_id` that attaches `static_gas`. The unused gas of
+            /// the current call is not passed on, so `static_gas` has to cover the whole execution
+            /// of the call. The call attaches no deposit unless it is configured on the builder.
+            pub fn ext(account_id: near_sdk::AccountId, static_gas: near_sdk::Gas) -> #builder_ident {
+                #builder_ident { account_id, deposit: 0, static_gas }
+            }
+
+            impl #builder_ident {
+                /// Sets the amount of yoctoNEAR attached to the call.
+                pub fn with_attached_deposit(mut self, amount: near_sdk::Balance) -> Self {
+                    self.deposit = amount;
+                    self
+                }
+
+                #methods
             }
         }
     }
@@ -85,6 +129,51 @@ mod tests {
                         __gas,
                     )
                 }
+                #[doc = " Builder of calls to the contract that implements `ExternalCrossContract`, created with [`ext`]."]
+                #[must_use]
+                pub struct ExternalCrossContractExt {
+                    account_id: near_sdk::AccountId,
+                    deposit: near_sdk::Balance,
+                    static_gas: near_sdk::Gas,
+                }
+                /// Starts building a call to `account_id` that attaches `static_gas`. The unused gas of
+                /// the current call is not passed on, so `static_gas` has to cover the whole execution
+                /// of the call. The call attaches no deposit unless it is configured on the builder.
+                pub fn ext(account_id: near_sdk::AccountId, static_gas: near_sdk::Gas) -> ExternalCrossContractExt {
+                    ExternalCrossContractExt { account_id, deposit: 0, static_gas }
+                }
+                impl ExternalCrossContractExt {
+                    /// Sets the amount of yoctoNEAR attached to the call.
+                    pub fn with_attached_deposit(mut self, amount: near_sdk::Balance) -> Self {
+                        self.deposit = amount;
+                        self
+                    }
+                    pub fn merge_sort(self, arr: Vec<u8>,) -> near_sdk::Promise {
+                        #[derive(near_sdk :: serde :: Serialize)]
+                        #[serde(crate = "near_sdk::serde")]
+                        struct Input {
+                            arr: Vec<u8>,
+                        }
+                        let args = Input { arr, };
+                        let args = near_sdk::serde_json::to_vec(&args)
+                            .expect("Failed to serialize the cross contract args using JSON.");
+                        near_sdk::Promise::new(self.account_id).function_call(
+                            b"merge_sort".to_vec(),
+                            args,
+                            self.deposit,
+                            self.static_gas,
+                        )
+                    }
+                    pub fn merge(self,) -> near_sdk::Promise {
+                        let args = vec![];
+                        near_sdk::Promise::new(self.account_id).function_call(
+                            b"merge".to_vec(),
+                            args,
+                            self.deposit,
+                            self.static_gas,
+                        )
+                    }
+                }
             }
         };
         assert_eq!(actual.to_string(), expected.to_string());
@@ -128,6 +217,41 @@ mod tests {
                     __gas,
                 )
             }
+            #[doc = " Builder of calls to the contract that implements `TestExt`, created with [`ext`]."]
+            #[must_use]
+            pub struct TestExtExt {
+                account_id: near_sdk::AccountId,
+                deposit: near_sdk::Balance,
+                static_gas: near_sdk::Gas,
+            }
+            /// Starts building a call to `account_id` that attaches `static_gas`. The unused gas of
+            /// the current call is not passed on, so `static_gas` has to cover the whole execution
+            /// of the call. The call attaches no deposit unless it is configured on the builder.
+            pub fn ext(account_id: near_sdk::AccountId, static_gas: near_sdk::Gas) -> TestExtExt {
+                TestExtExt { account_id, deposit: 0, static_gas }
+            }
+            impl TestExtExt {
+                /// Sets the amount of yoctoNEAR attached to the call.
+                pub fn with_attached_deposit(mut self, amount: near_sdk::Balance) -> Self {
+                    self.deposit = amount;
+                    self
+                }
+                pub fn test(self, v: Vec<String>,) -> near_sdk::Promise {
+                    #[derive(near_sdk :: borsh :: BorshSerialize)]
+                    struct Input {
+                        v: Vec<String>,
+                    }
+                    let args = Input { v, };
+                    let args = near_sdk::borsh::BorshSerialize::try_to_vec(&args)
+                        .expect("Failed to serialize the cross contract args using Borsh.");
+                    near_sdk::Promise::new(self.account_id).function_call(
+                        b"test".to_vec(),
+                        args,
+                        self.deposit,
+                        self.static_gas,
+                    )
+                }
+            }
         }
         };
         assert_eq!(actual.to_string(), expected.to_string());
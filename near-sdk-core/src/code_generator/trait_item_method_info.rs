@@ -29,6 +29,30 @@ impl TraitItemMethodInfo {
         }
     }
 
+    /// Generate the method of the call builder that performs the call with the configured
+    /// deposit and gas.
+    pub fn builder_method(&self) -> TokenStream2 {
+        let ident = &self.attr_sig_info.ident;
+        let ident_byte_str = &self.ident_byte_str;
+        let pat_type_list = self.attr_sig_info.pat_type_list();
+        let serialize = TraitItemMethodInfo::generate_serialier(
+            &self.attr_sig_info,
+            &self.attr_sig_info.result_serializer,
+        );
+        quote! {
+            pub fn #ident(self, #pat_type_list) -> near_sdk::Promise {
+                #serialize
+                near_sdk::Promise::new(self.account_id)
+                .function_call(
+                    #ident_byte_str.to_vec(),
+                    args,
+                    self.deposit,
+                    self.static_gas,
+                )
+            }
+        }
+    }
+
     pub fn generate_serialier(
         attr_sig_info: &AttrSigInfo,
         serializer: &SerializerType,
@@ -41,7 +65,7 @@ impl TraitItemMethodInfo {
         let constructor_call = attr_sig_info.constructor_expr();
         let constructor = quote! { let args = #constructor_call; };
         let value_ser = match serializer {
-            SerializerType::JSON => quote! {
+            SerializerType::JSON | SerializerType::Auto => quote! {
                 let args = near_sdk::serde_json::to_vec(&args).expect("Failed to serialize the cross contract args using JSON.");
             },
             SerializerType::Borsh => quote! {
@@ -2,6 +2,7 @@ use crate::info_extractor::serializer_attr::SerializerAttr;
 use crate::info_extractor::SerializerType;
 use quote::ToTokens;
 use syn::export::Span;
+use syn::spanned::Spanned;
 use syn::{Attribute, Error, Ident, Pat, PatType, Token, Type};
 
 pub enum BindgenArgType {
@@ -17,6 +18,9 @@ pub enum BindgenArgType {
 pub struct ArgInfo {
     /// Attributes not related to bindgen.
     pub non_bindgen_attrs: Vec<Attribute>,
+    /// Serde attributes, like `#[serde(default)]`, that are applied to the field of the input
+    /// struct.
+    pub serde_attrs: Vec<Attribute>,
     /// The `binding` part of `ref mut binding @ SUBPATTERN: TYPE` argument.
     pub ident: Ident,
     /// Whether pattern has a preceded `ref`.
@@ -41,6 +45,7 @@ impl ArgInfo {
     /// Extract near-sdk specific argument info.
     pub fn new(original: &mut PatType) -> syn::Result<Self> {
         let mut non_bindgen_attrs = vec![];
+        let mut serde_attrs = vec![];
         let pat_reference;
         let pat_mutability;
         let ident;
@@ -79,8 +84,17 @@ impl ArgInfo {
                 }
                 "serializer" => {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
+                    if serializer.serializer_type == SerializerType::Auto {
+                        return Err(Error::new(
+                            attr.span(),
+                            "`#[serializer(auto)]` applies to all arguments, place it on the method.",
+                        ));
+                    }
                     serializer_ty = serializer.serializer_type;
                 }
+                "serde" => {
+                    serde_attrs.push((*attr).clone());
+                }
                 _ => {
                     non_bindgen_attrs.push((*attr).clone());
                 }
@@ -89,11 +103,15 @@ impl ArgInfo {
 
         original.attrs.retain(|attr| {
             let attr_str = attr.path.to_token_stream().to_string();
-            attr_str != "callback" && attr_str != "callback_vec" && attr_str != "serializer"
+            attr_str != "callback"
+                && attr_str != "callback_vec"
+                && attr_str != "serializer"
+                && attr_str != "serde"
         });
 
         Ok(Self {
             non_bindgen_attrs,
+            serde_attrs,
             ident,
             pat_reference,
             pat_mutability,
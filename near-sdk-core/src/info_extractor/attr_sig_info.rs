@@ -1,11 +1,13 @@
 use crate::info_extractor::arg_info::{ArgInfo, BindgenArgType};
+use crate::info_extractor::bindgen_method_attr::BindgenMethodAttr;
+use crate::info_extractor::deprecated_method_attr::DeprecatedMethodAttr;
 use crate::info_extractor::serializer_attr::SerializerAttr;
 use crate::info_extractor::SerializerType;
 use crate::info_extractor::{InitAttr, MethodType};
 use quote::ToTokens;
 use syn::export::Span;
 use syn::spanned::Spanned;
-use syn::{Attribute, Error, FnArg, Ident, Receiver, ReturnType, Signature};
+use syn::{Attribute, Error, FnArg, Ident, LitInt, Receiver, ReturnType, Signature};
 
 /// Information extracted from method attributes and signature.
 pub struct AttrSigInfo {
@@ -21,6 +23,10 @@ pub struct AttrSigInfo {
     pub is_payable: bool,
     /// Whether method can accept calls from self (current account)
     pub is_private: bool,
+    /// The gas budget of the method that is checked in debug builds.
+    pub max_gas: Option<LitInt>,
+    /// Set if the method is marked with `#[deprecated_method]`, every call logs a warning.
+    pub deprecated: Option<DeprecatedMethodAttr>,
     /// The serializer that we use for `env::input()`.
     pub input_serializer: SerializerType,
     /// The serializer that we use for the return type.
@@ -64,6 +70,9 @@ impl AttrSigInfo {
         let mut method_type = MethodType::Regular;
         let mut is_payable = false;
         let mut is_private = false;
+        let mut max_gas = None;
+        let mut deprecated = None;
+        let mut auto_input_span = None;
         // By the default we serialize the result with JSON.
         let mut result_serializer = SerializerType::JSON;
 
@@ -86,8 +95,31 @@ impl AttrSigInfo {
                 "private" => {
                     is_private = true;
                 }
+                "near_bindgen" => {
+                    let bindgen_attr: BindgenMethodAttr = syn::parse2(attr.tokens.clone())?;
+                    max_gas = bindgen_attr.max_gas;
+                }
+                "deprecated_method" => {
+                    deprecated = Some(syn::parse2(attr.tokens.clone())?);
+                }
+                "serializer" => {
+                    let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
+                    if serializer.serializer_type != SerializerType::Auto {
+                        return Err(Error::new(
+                            attr.span(),
+                            "Only `#[serializer(auto)]` is supported on methods, choose the serializer of the arguments on the arguments.",
+                        ));
+                    }
+                    auto_input_span = Some(attr.span());
+                }
                 "result_serializer" => {
                     let serializer: SerializerAttr = syn::parse2(attr.tokens.clone())?;
+                    if serializer.serializer_type == SerializerType::Auto {
+                        return Err(Error::new(
+                            attr.span(),
+                            "The result of a method can't be serialized with `auto`, choose `json` or `borsh`.",
+                        ));
+                    }
                     result_serializer = serializer.serializer_type;
                 }
                 _ => {
@@ -113,7 +145,7 @@ impl AttrSigInfo {
                 }
             } else {
                 return Err(Error::new(
-                    payable_attr.span(),
+                    receiver.span(),
                     "Init methods can't have `self` attribute",
                 ));
             }
@@ -139,6 +171,8 @@ impl AttrSigInfo {
             method_type,
             is_payable,
             is_private,
+            max_gas,
+            deprecated,
             result_serializer,
             receiver,
             returns,
@@ -156,7 +190,25 @@ impl AttrSigInfo {
                     "Input arguments should be all of the same serialization type.",
                 ));
             };
-        result.input_serializer = input_serializer;
+        if input_serializer == SerializerType::Borsh {
+            if let Some(attr) = result.input_args().flat_map(|arg| arg.serde_attrs.iter()).next() {
+                return Err(Error::new(
+                    attr.span(),
+                    "Serde attributes are only supported for arguments serialized with JSON.",
+                ));
+            }
+        }
+        if let Some(span) = auto_input_span {
+            if input_serializer != SerializerType::JSON {
+                return Err(Error::new(
+                    span,
+                    "`#[serializer(auto)]` can't be combined with serializers on the arguments.",
+                ));
+            }
+            result.input_serializer = SerializerType::Auto;
+        } else {
+            result.input_serializer = input_serializer;
+        }
         Ok(result)
     }
 
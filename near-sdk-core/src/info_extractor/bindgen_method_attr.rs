@@ -0,0 +1,33 @@
+use proc_macro2::Ident;
+use syn::parse::{Parse, ParseStream};
+use syn::{parenthesized, Error, LitInt, Token};
+
+/// Options of a `#[near_bindgen(...)]` attribute placed on a method.
+pub struct BindgenMethodAttr {
+    /// The gas budget of the method given with `max_gas = N`.
+    pub max_gas: Option<LitInt>,
+}
+
+impl Parse for BindgenMethodAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut max_gas = None;
+        let content;
+        parenthesized!(content in input);
+        while !content.is_empty() {
+            let ident: Ident = content.parse()?;
+            match ident.to_string().as_str() {
+                "max_gas" => {
+                    content.parse::<Token![=]>()?;
+                    let value: LitInt = content.parse()?;
+                    value.base10_parse::<u64>()?;
+                    max_gas = Some(value);
+                }
+                _ => return Err(Error::new(ident.span(), "Unsupported near_bindgen attribute.")),
+            }
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        Ok(Self { max_gas })
+    }
+}
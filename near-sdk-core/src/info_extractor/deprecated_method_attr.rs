@@ -0,0 +1,31 @@
+use syn::parse::{Parse, ParseStream};
+use syn::token::Paren;
+use syn::{Error, LitStr, Token};
+
+/// Options of a `#[deprecated_method]` attribute placed on a method.
+pub struct DeprecatedMethodAttr {
+    /// The method to call instead, given with `use = "name"`.
+    pub replacement: Option<LitStr>,
+}
+
+impl Parse for DeprecatedMethodAttr {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let replacement = if input.peek(Paren) {
+            let content;
+            let _paren_token = syn::parenthesized!(content in input);
+            if !content.peek(Token![use]) {
+                return Err(Error::new(content.span(), "Expected `use = \"method_name\"`."));
+            }
+            content.parse::<Token![use]>()?;
+            content.parse::<Token![=]>()?;
+            let replacement: LitStr = content.parse()?;
+            if !content.is_empty() {
+                return Err(Error::new(content.span(), "Unsupported deprecated_method attribute."));
+            }
+            Some(replacement)
+        } else {
+            None
+        };
+        Ok(Self { replacement })
+    }
+}
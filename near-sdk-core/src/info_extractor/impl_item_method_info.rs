@@ -1,5 +1,5 @@
 use crate::info_extractor::AttrSigInfo;
-use syn::{ImplItemMethod, Type, Visibility};
+use syn::{parse_quote, ImplItemMethod, Type, Visibility};
 
 /// Information extracted from `ImplItemMethod`.
 pub struct ImplItemMethodInfo {
@@ -14,8 +14,32 @@ pub struct ImplItemMethodInfo {
 impl ImplItemMethodInfo {
     /// Process the method and extract information important for near-sdk.
     pub fn new(original: &mut ImplItemMethod, struct_type: Type) -> syn::Result<Self> {
-        let ImplItemMethod { attrs, sig, .. } = original;
+        let ImplItemMethod { attrs, sig, block, .. } = original;
         let attr_signature_info = AttrSigInfo::new(attrs, sig)?;
+        if let Some(deprecated) = &attr_signature_info.deprecated {
+            // Logged from the method body like the gas guard below, so unit tests see the warning.
+            let message = match &deprecated.replacement {
+                Some(replacement) => format!(
+                    "Method {} is deprecated, use {}",
+                    attr_signature_info.ident,
+                    replacement.value()
+                ),
+                None => format!("Method {} is deprecated", attr_signature_info.ident),
+            };
+            block.stmts.insert(0, parse_quote!(near_sdk::env::log_str(#message);));
+        }
+        if let Some(max_gas) = &attr_signature_info.max_gas {
+            // The guard checks the budget when it's dropped at the end of the method body, so this
+            // also applies when the method is called directly in unit tests.
+            let ident_str = attr_signature_info.ident.to_string();
+            block.stmts.insert(
+                0,
+                parse_quote! {
+                    #[cfg(debug_assertions)]
+                    let __near_max_gas_guard = near_sdk::MaxGasGuard::new(#ident_str, near_sdk::Gas(#max_gas));
+                },
+            );
+        }
         let is_public = matches!(original.vis, Visibility::Public(_));
         Ok(Self { attr_signature_info, is_public, struct_type })
     }
@@ -15,7 +15,7 @@ impl Parse for InitAttr {
             let ident: Ident = content.parse()?;
             match ident.to_string().as_str() {
                 "ignore_state" => true,
-                _ => return Err(Error::new(input.span(), "Unsupported init attribute.")),
+                _ => return Err(Error::new(ident.span(), "Unsupported init attribute.")),
             }
         } else {
             false
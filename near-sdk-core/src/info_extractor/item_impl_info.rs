@@ -1,11 +1,13 @@
 use crate::ImplItemMethodInfo;
 use syn::spanned::Spanned;
-use syn::{Error, ImplItem, ItemImpl, Type};
+use syn::{Error, Ident, ImplItem, ItemImpl, Type};
 
 /// Information extracted from `impl` section.
 pub struct ItemImplInfo {
     /// Whether this is a trait implementation.
     pub is_trait_impl: bool,
+    /// The name of the implemented trait, if this is a trait implementation.
+    pub trait_ident: Option<Ident>,
     /// The type for which this `impl` is written.
     pub ty: Type,
     /// Info extracted for each method.
@@ -21,6 +23,11 @@ impl ItemImplInfo {
             ));
         }
         let is_trait_impl = original.trait_.is_some();
+        let trait_ident = original
+            .trait_
+            .as_ref()
+            .and_then(|(_, path, _)| path.segments.last())
+            .map(|segment| segment.ident.clone());
         let ty = (*original.self_ty.as_ref()).clone();
 
         let mut methods = vec![];
@@ -30,6 +37,6 @@ impl ItemImplInfo {
                 methods.push(method_info);
             }
         }
-        Ok(Self { is_trait_impl, ty, methods })
+        Ok(Self { is_trait_impl, trait_ident, ty, methods })
     }
 }
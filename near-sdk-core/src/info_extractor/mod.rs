@@ -1,6 +1,9 @@
 mod serializer_attr;
 pub use serializer_attr::SerializerAttr;
 
+mod bindgen_method_attr;
+pub use bindgen_method_attr::BindgenMethodAttr;
+
 mod arg_info;
 pub use arg_info::{ArgInfo, BindgenArgType};
 
@@ -21,6 +24,9 @@ mod item_impl_info;
 mod init_attr;
 pub use init_attr::InitAttr;
 
+mod deprecated_method_attr;
+pub use deprecated_method_attr::DeprecatedMethodAttr;
+
 pub use item_impl_info::ItemImplInfo;
 
 /// Type of serialization we use.
@@ -28,6 +34,9 @@ pub use item_impl_info::ItemImplInfo;
 pub enum SerializerType {
     JSON,
     Borsh,
+    /// JSON, or Borsh if the input is not valid JSON. Only used for the input of a method, which
+    /// is marked with `#[serializer(auto)]`.
+    Auto,
 }
 
 /// Type of the method.
@@ -17,6 +17,7 @@ impl Parse for SerializerAttr {
         let serializer_type = match ident.to_string().as_str() {
             "borsh" => SerializerType::Borsh,
             "json" => SerializerType::JSON,
+            "auto" => SerializerType::Auto,
             _ => return Err(Error::new(input.span(), "Unsupported serializer type.")),
         };
         Ok(Self { paren_token, serializer_type })
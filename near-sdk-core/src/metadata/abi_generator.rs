@@ -0,0 +1,201 @@
+use crate::{ImplItemMethodInfo, ItemImplInfo, MethodType};
+
+use inflector::Inflector;
+use quote::{format_ident, quote, ToTokens};
+use syn::export::TokenStream2;
+use syn::{ReturnType, Type};
+
+impl ItemImplInfo {
+    /// Generates the function that returns JSON ABI of the methods exported from this `impl`.
+    ///
+    /// For trait implementations the snake-cased trait name is appended to `__contract_abi`, e.g.
+    /// `__contract_abi_my_trait`. For inherent `impl` sections `impl_` and the name of the first
+    /// method of the section are appended, e.g. `__contract_abi_impl_new`: a contract can split its
+    /// methods over several inherent sections, and the method names are unique among them. So a
+    /// contract can have ABI for several `#[near_bindgen]` sections. Sections without methods
+    /// generate nothing. On `wasm32` the function is also exported under the same name and returns
+    /// the ABI with `env::value_return`.
+    pub fn abi_code(&self) -> TokenStream2 {
+        let fn_name = match (&self.trait_ident, self.methods.first()) {
+            (Some(trait_ident), _) => {
+                format_ident!("__contract_abi_{}", trait_ident.to_string().to_snake_case())
+            }
+            (None, Some(first)) => {
+                format_ident!("__contract_abi_impl_{}", first.attr_signature_info.ident)
+            }
+            (None, None) => return TokenStream2::new(),
+        };
+        let export_name = fn_name.to_string();
+        let export_fn_name = format_ident!("{}_export", fn_name);
+        let methods: Vec<String> = self
+            .methods
+            .iter()
+            .filter(|method| method.is_public || self.is_trait_impl)
+            .map(|method| method.abi_json())
+            .collect();
+        let abi = format!("{{\"methods\":[{}]}}", methods.join(","));
+        quote! {
+            #[doc(hidden)]
+            pub fn #fn_name() -> String {
+                #abi.to_string()
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            #[export_name = #export_name]
+            pub extern "C" fn #export_fn_name() {
+                near_sdk::env::value_return(#abi.as_bytes());
+            }
+        }
+    }
+}
+
+impl ImplItemMethodInfo {
+    /// Generates JSON ABI entry for this method.
+    ///
+    /// # Example:
+    /// The following method:
+    /// ```ignore
+    /// pub fn f(&mut self, arg0: Vec<String>, #[callback] arg1: u64) -> Option<u64> { }
+    /// ```
+    /// will produce this entry:
+    /// ```ignore
+    /// {"name":"f","kind":"call","args":[{"name":"arg0","type":"Vec<String>"}],"returns":"Option<u64>"}
+    /// ```
+    /// Only the arguments that are read from the input are listed, callback arguments are skipped.
+    pub fn abi_json(&self) -> String {
+        let sig = &self.attr_signature_info;
+        let kind = match sig.method_type {
+            MethodType::View => "view",
+            MethodType::Regular => "call",
+            MethodType::Init | MethodType::InitIgnoreState => "init",
+        };
+        let args: Vec<String> = sig
+            .input_args()
+            .map(|arg| {
+                format!(
+                    "{{\"name\":{},\"type\":{}}}",
+                    json_string(&arg.ident.to_string()),
+                    json_string(&type_to_string(&arg.original.ty))
+                )
+            })
+            .collect();
+        // Init methods return the contract state which is written to the storage, not returned.
+        let returns = match &sig.returns {
+            ReturnType::Type(_, ty) if kind != "init" => json_string(&type_to_string(ty)),
+            _ => "null".to_string(),
+        };
+        format!(
+            "{{\"name\":{},\"kind\":\"{}\",\"args\":[{}],\"returns\":{}}}",
+            json_string(&sig.ident.to_string()),
+            kind,
+            args.join(","),
+            returns
+        )
+    }
+}
+
+/// Prints the type the way it is usually written in the source, e.g. `Vec<String>` instead of
+/// `Vec < String >` produced by the token stream.
+fn type_to_string(ty: &Type) -> String {
+    let tokens = ty.to_token_stream().to_string();
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    let mut res = String::new();
+    for token in tokens.split_whitespace() {
+        let separate = match (res.chars().last(), token.chars().next()) {
+            (Some(prev), Some(next)) => {
+                (is_word_char(prev) && (is_word_char(next) || next == '\''))
+                    || prev == ','
+                    || prev == ';'
+            }
+            _ => false,
+        };
+        if separate {
+            res.push(' ');
+        }
+        res.push_str(token);
+    }
+    res
+}
+
+fn json_string(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use quote::quote;
+    use syn::ItemImpl;
+    use crate::ItemImplInfo;
+
+    #[test]
+    fn inherent_impl() {
+        let mut item: ItemImpl = syn::parse2(quote! {
+            impl Hello {
+                #[init]
+                pub fn new(owner: AccountId) -> Self { }
+                pub fn get(&self, keys: Vec<String>, range: (u64, u64)) -> Option<HashMap<String, [u8; 32]>> { }
+                pub fn set(&mut self, #[callback] res: u64, value: &str) { }
+                fn private_helper(&self) { }
+            }
+        }).unwrap();
+        let info = ItemImplInfo::new(&mut item).unwrap();
+        let actual = info.abi_code();
+        let abi = concat!(
+            r#"{"methods":["#,
+            r#"{"name":"new","kind":"init","args":[{"name":"owner","type":"AccountId"}],"returns":null},"#,
+            r#"{"name":"get","kind":"view","args":[{"name":"keys","type":"Vec<String>"},{"name":"range","type":"(u64, u64)"}],"returns":"Option<HashMap<String, [u8; 32]>>"},"#,
+            r#"{"name":"set","kind":"call","args":[{"name":"value","type":"&str"}],"returns":null}"#,
+            r#"]}"#
+        );
+        let expected = quote!(
+            #[doc(hidden)]
+            pub fn __contract_abi_impl_new() -> String {
+                #abi.to_string()
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            #[export_name = "__contract_abi_impl_new"]
+            pub extern "C" fn __contract_abi_impl_new_export() {
+                near_sdk::env::value_return(#abi.as_bytes());
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn trait_impl() {
+        let mut item: ItemImpl = syn::parse2(quote! {
+            impl MyTrait for Hello {
+                fn method(&self) -> String { }
+            }
+        }).unwrap();
+        let info = ItemImplInfo::new(&mut item).unwrap();
+        let actual = info.abi_code();
+        let abi = r#"{"methods":[{"name":"method","kind":"view","args":[],"returns":"String"}]}"#;
+        let expected = quote!(
+            #[doc(hidden)]
+            pub fn __contract_abi_my_trait() -> String {
+                #abi.to_string()
+            }
+
+            #[cfg(target_arch = "wasm32")]
+            #[export_name = "__contract_abi_my_trait"]
+            pub extern "C" fn __contract_abi_my_trait_export() {
+                near_sdk::env::value_return(#abi.as_bytes());
+            }
+        );
+        assert_eq!(expected.to_string(), actual.to_string());
+    }
+
+    #[test]
+    fn empty_inherent_impl() {
+        let mut item: ItemImpl = syn::parse2(quote! {
+            impl Hello {
+                const LIMIT: u64 = 10;
+            }
+        }).unwrap();
+        let info = ItemImplInfo::new(&mut item).unwrap();
+        assert!(info.abi_code().is_empty());
+    }
+}
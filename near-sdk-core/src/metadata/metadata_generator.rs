@@ -46,7 +46,7 @@ impl ImplItemMethodInfo {
             // If input args are JSON then we need to additionally specify schema for them.
             let additional_schema = match &self.attr_signature_info.input_serializer {
                 SerializerType::Borsh => TokenStream2::new(),
-                SerializerType::JSON => quote! {
+                SerializerType::JSON | SerializerType::Auto => quote! {
                     #[derive(borsh::BorshSchema)]
                 },
             };
@@ -10,12 +10,37 @@ use syn::visit::Visit;
 use syn::{File, ItemEnum, ItemImpl, ItemStruct, ItemTrait};
 
 #[proc_macro_attribute]
-pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn near_bindgen(attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Ok(input) = syn::parse::<ItemStruct>(item.clone()) {
         let struct_proxy = generate_proxy_struct(&input);
+        let schema_code = match state_schema_code(attr, &input.ident) {
+            Ok(x) => x,
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
         TokenStream::from(quote! {
             #input
             #struct_proxy
+            #schema_code
+        })
+    } else if let Ok(input) = syn::parse::<ItemEnum>(item.clone()) {
+        let enum_proxy = match generate_proxy_enum(&input) {
+            Ok(x) => x,
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
+        let schema_code = match state_schema_code(attr, &input.ident) {
+            Ok(x) => x,
+            Err(err) => {
+                return err.to_compile_error().into();
+            }
+        };
+        TokenStream::from(quote! {
+            #input
+            #enum_proxy
+            #schema_code
         })
     } else if let Ok(mut input) = syn::parse::<ItemImpl>(item) {
         let item_impl_info = match ItemImplInfo::new(&mut input) {
@@ -24,13 +49,22 @@ pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 return err.to_compile_error().into();
             }
         };
+        if cfg!(feature = "debug-panic-context") {
+            item_impl_info.add_method_context(&mut input);
+        }
         let generated_code = item_impl_info.wrapper_code();
         // Add helper type for simulation testing only if not wasm32
         let marshalled_code = item_impl_info.marshall_code();
+        let abi_code = if cfg!(feature = "abi") {
+            item_impl_info.abi_code()
+        } else {
+            quote! {}
+        };
         TokenStream::from(quote! {
             #marshalled_code
             #input
             #generated_code
+            #abi_code
         })
     } else {
         TokenStream::from(
@@ -43,6 +77,20 @@ pub fn near_bindgen(_attr: TokenStream, item: TokenStream) -> TokenStream {
     }
 }
 
+/// The schema function of a contract state marked with `#[near_bindgen(schema)]`, which is only
+/// generated with the `schema` feature.
+fn state_schema_code(
+    attr: TokenStream,
+    ident: &proc_macro2::Ident,
+) -> syn::Result<proc_macro2::TokenStream> {
+    let schema = parse_state_args(attr.into())?;
+    if schema && cfg!(feature = "schema") {
+        Ok(generate_state_schema(ident))
+    } else {
+        Ok(quote! {})
+    }
+}
+
 #[proc_macro_attribute]
 pub fn ext_contract(attr: TokenStream, item: TokenStream) -> TokenStream {
     if let Ok(mut input) = syn::parse::<ItemTrait>(item) {
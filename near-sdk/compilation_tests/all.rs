@@ -4,6 +4,9 @@ fn compilation_tests() {
     let t = trybuild::TestCases::new();
     t.pass("compilation_tests/regular.rs");
     t.pass("compilation_tests/private.rs");
+    t.pass("compilation_tests/callback.rs");
+    t.pass("compilation_tests/payable.rs");
+    t.pass("compilation_tests/max_gas.rs");
     t.pass("compilation_tests/trait_impl.rs");
     t.pass("compilation_tests/metadata.rs");
     t.compile_fail("compilation_tests/metadata_invalid_rust.rs");
@@ -13,9 +16,18 @@ fn compilation_tests() {
     t.pass("compilation_tests/references.rs");
     t.pass("compilation_tests/init_function.rs");
     t.pass("compilation_tests/init_ignore_state.rs");
+    t.compile_fail("compilation_tests/init_self.rs");
+    t.compile_fail("compilation_tests/init_unsupported_attr.rs");
     t.pass("compilation_tests/no_default.rs");
     t.pass("compilation_tests/lifetime_method.rs");
     t.pass("compilation_tests/cond_compilation.rs");
     t.compile_fail("compilation_tests/payable_view.rs");
     t.pass("compilation_tests/borsh_storage_key.rs");
+    t.pass("compilation_tests/serde_default_args.rs");
+    t.pass("compilation_tests/flatten_args.rs");
+    t.pass("compilation_tests/borsh_serializer.rs");
+    t.pass("compilation_tests/serializer_auto.rs");
+    t.pass("compilation_tests/enum_state.rs");
+    t.compile_fail("compilation_tests/enum_state_discriminant.rs");
+    t.compile_fail("compilation_tests/state_unsupported_arg.rs");
 }
@@ -0,0 +1,43 @@
+//! Methods that read their arguments and write their result with Borsh instead of JSON.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{ext_contract, near_bindgen};
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct Transfer {
+    receiver_id: String,
+    amount: u128,
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Ledger {
+    total: u128,
+}
+
+#[near_bindgen]
+impl Ledger {
+    #[result_serializer(borsh)]
+    pub fn apply(&mut self, #[serializer(borsh)] transfer: Transfer) -> u128 {
+        self.total += transfer.amount;
+        self.total
+    }
+
+    #[result_serializer(borsh)]
+    pub fn total(&self) -> u128 {
+        self.total
+    }
+
+    /// JSON stays the default, it can be mixed with Borsh methods.
+    pub fn total_json(&self) -> near_sdk::json_types::U128 {
+        self.total.into()
+    }
+}
+
+#[ext_contract]
+pub trait ExtLedger {
+    #[result_serializer(borsh)]
+    fn apply(&mut self, #[serializer(borsh)] transfer: Transfer) -> u128;
+}
+
+fn main() {}
@@ -0,0 +1,35 @@
+//! Callbacks that read the results of the promises they were scheduled after.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Counter {
+    total: u64,
+}
+
+#[near_bindgen]
+impl Counter {
+    /// Adds the result of a single promise.
+    #[private]
+    pub fn on_value(&mut self, #[callback] x: u64) -> u64 {
+        self.total += x;
+        self.total
+    }
+
+    /// Adds the Borsh-encoded result of a single promise and a regular argument.
+    #[private]
+    pub fn on_borsh_value(&mut self, #[callback] #[serializer(borsh)] x: u64, extra: u64) {
+        self.total += x + extra;
+    }
+
+    /// Adds the results of all the promises joined with `and`.
+    #[private]
+    pub fn on_values(&mut self, #[callback_vec] values: Vec<u64>) -> u64 {
+        self.total += values.iter().sum::<u64>();
+        self.total
+    }
+}
+
+fn main() {}
@@ -0,0 +1,32 @@
+//! Smart contract whose state is an enum.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize)]
+enum Auction {
+    Open { highest_bid: u128 },
+    Closed,
+}
+
+impl Default for Auction {
+    fn default() -> Self {
+        Self::Open { highest_bid: 0 }
+    }
+}
+
+#[near_bindgen]
+impl Auction {
+    pub fn bid(&mut self, amount: u128) {
+        if let Self::Open { highest_bid } = self {
+            *highest_bid = (*highest_bid).max(amount);
+        }
+    }
+
+    pub fn close(&mut self) {
+        *self = Self::Closed;
+    }
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+//! Explicit discriminants of the contract state have to match the positions Borsh uses.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize)]
+enum Status {
+    Paused = 1,
+    Active = 0,
+}
+
+fn main() {}
@@ -0,0 +1,36 @@
+//! Methods that take their whole JSON input as a single struct with `#[serde(flatten)]`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+use near_sdk::{ext_contract, near_bindgen};
+
+#[derive(Serialize, Deserialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct IncArgs {
+    by: u32,
+    times: u32,
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    pub fn inc(&mut self, #[serde(flatten)] args: IncArgs) {
+        self.value += args.by * args.times;
+    }
+
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+}
+
+#[ext_contract]
+pub trait ExtIncrementer {
+    fn inc(&mut self, #[serde(flatten)] args: IncArgs);
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+//! Init methods can't take `self`, since the state doesn't exist yet.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    #[init]
+    pub fn new(&self, starting_value: u32) -> Self {
+        Self { value: starting_value }
+    }
+}
+
+fn main() {}
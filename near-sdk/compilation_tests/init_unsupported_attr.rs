@@ -0,0 +1,20 @@
+//! Only `ignore_state` is supported as an argument of `#[init]`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    #[init(ignore_storage)]
+    pub fn new(starting_value: u32) -> Self {
+        Self { value: starting_value }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,39 @@
+//! Methods are not payable unless they are marked with `#[payable]`.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Donations {
+    total: u128,
+}
+
+#[near_bindgen]
+impl Donations {
+    /// Accepts deposits.
+    #[payable]
+    pub fn donate(&mut self) {
+        self.total += env::attached_deposit();
+    }
+
+    /// Panics if a deposit is attached.
+    pub fn reset(&mut self) {
+        self.total = 0;
+    }
+
+    /// Only callable by the contract itself and panics if a deposit is attached.
+    #[private]
+    pub fn on_refund(&mut self, amount: u128) {
+        self.total -= amount;
+    }
+
+    /// Only callable by the contract itself, but accepts deposits.
+    #[private]
+    #[payable]
+    pub fn donate_from_self(&mut self) {
+        self.total += env::attached_deposit();
+    }
+}
+
+fn main() {}
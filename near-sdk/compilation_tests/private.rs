@@ -1,4 +1,5 @@
-//! Regular smart contract.
+//! Private methods, including a callback and an init method. Note that `env` is not imported, so
+//! the generated checks must not rely on it being in scope.
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use near_sdk::near_bindgen;
@@ -11,10 +12,23 @@ struct Incrementer {
 
 #[near_bindgen]
 impl Incrementer {
+    #[init]
+    #[private]
+    pub fn new(value: u32) -> Self {
+        Self { value }
+    }
+
     #[private]
     pub fn inc(&mut self, by: u32) {
         self.value += by;
     }
+
+    /// Callback of a cross-contract call, it can only be called by the contract itself.
+    #[private]
+    #[payable]
+    pub fn on_inc(&mut self, #[callback] by: u32) {
+        self.value += by;
+    }
 }
 
 fn main() {}
@@ -0,0 +1,24 @@
+//! Arguments marked with `#[serde(default)]` can be omitted from the JSON input.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::{ext_contract, near_bindgen};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+#[near_bindgen]
+impl Incrementer {
+    pub fn inc(&mut self, by: u32, #[serde(default)] times: u32) {
+        self.value += by * times.max(1);
+    }
+}
+
+#[ext_contract]
+pub trait ExtIncrementer {
+    fn inc(&mut self, by: u32, #[serde(default)] times: u32);
+}
+
+fn main() {}
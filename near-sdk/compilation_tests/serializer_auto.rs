@@ -0,0 +1,28 @@
+//! Method that accepts its arguments as JSON or Borsh.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, BorshDeserialize, BorshSerialize)]
+pub struct Transfer {
+    receiver_id: String,
+    amount: u64,
+}
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Ledger {
+    total: u64,
+}
+
+#[near_bindgen]
+impl Ledger {
+    #[serializer(auto)]
+    pub fn apply(&mut self, transfer: Transfer) -> u64 {
+        self.total += transfer.amount;
+        self.total
+    }
+}
+
+fn main() {}
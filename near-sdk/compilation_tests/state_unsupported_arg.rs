@@ -0,0 +1,12 @@
+//! Only `schema` is supported as an argument of `near_bindgen` on the contract state.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+
+#[near_bindgen(abi)]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+struct Incrementer {
+    value: u32,
+}
+
+fn main() {}
@@ -7,6 +7,7 @@ use std::marker::PhantomData;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
+use crate::collections::register_prefix;
 use crate::env;
 use crate::IntoStorageKey;
 
@@ -74,7 +75,9 @@ where
     where
         S: IntoStorageKey,
     {
-        let mut this = Self { storage_key: storage_key.into_storage_key(), el: PhantomData };
+        let storage_key = storage_key.into_storage_key();
+        register_prefix::<Self>(&storage_key);
+        let mut this = Self { storage_key, el: PhantomData };
         if let Some(value) = value {
             this.set(&value);
         }
@@ -178,4 +181,29 @@ mod tests {
         assert!(a.is_some());
         assert_eq!(a.get(), Some(42));
     }
+
+    #[test]
+    pub fn test_lazy_deserialization() {
+        #[derive(BorshSerialize, BorshDeserialize)]
+        struct Contract {
+            value: LazyOption<Vec<u8>>,
+        }
+
+        test_env::setup();
+        let contract = Contract { value: LazyOption::new(b"a", Some(&vec![7u8; 1000])) };
+        let state = contract.try_to_vec().unwrap();
+
+        // Querying the used gas is a host call itself, so measure its cost to compare against.
+        let gas_start = env::used_gas();
+        let gas_before = env::used_gas();
+        let measurement_cost = gas_before - gas_start;
+
+        // Deserializing the contract only reads the storage key, not the value.
+        let contract = Contract::try_from_slice(&state).unwrap();
+        let gas_after_deserialize = env::used_gas();
+        assert_eq!(gas_after_deserialize - gas_before, measurement_cost);
+
+        assert_eq!(contract.value.get(), Some(vec![7u8; 1000]));
+        assert!(env::used_gas() - gas_after_deserialize > measurement_cost);
+    }
 }
@@ -5,7 +5,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use std::ops::Bound;
 
 use crate::collections::UnorderedMap;
-use crate::collections::{append, Vector};
+use crate::collections::{append, register_prefix, Vector};
 use crate::IntoStorageKey;
 
 /// TreeMap based on AVL-tree
@@ -52,6 +52,7 @@ where
         S: IntoStorageKey,
     {
         let prefix = prefix.into_storage_key();
+        register_prefix::<Self>(&prefix);
         Self {
             root: 0,
             val: UnorderedMap::new(append(&prefix, b'v')),
@@ -5,7 +5,7 @@ use std::marker::PhantomData;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use crate::collections::append_slice;
+use crate::collections::{append_slice, register_prefix};
 use crate::{env, IntoStorageKey};
 
 const ERR_KEY_SERIALIZATION: &[u8] = b"Cannot serialize key with Borsh";
@@ -26,7 +26,9 @@ impl<K, V> LookupMap<K, V> {
     where
         S: IntoStorageKey,
     {
-        Self { key_prefix: key_prefix.into_storage_key(), el: PhantomData }
+        let key_prefix = key_prefix.into_storage_key();
+        register_prefix::<Self>(&key_prefix);
+        Self { key_prefix, el: PhantomData }
     }
 
     fn raw_key_to_storage_key(&self, raw_key: &[u8]) -> Vec<u8> {
@@ -5,7 +5,7 @@ use std::marker::PhantomData;
 
 use borsh::{BorshDeserialize, BorshSerialize};
 
-use crate::collections::append_slice;
+use crate::collections::{append_slice, register_prefix};
 use crate::{env, IntoStorageKey};
 
 const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element with Borsh";
@@ -24,7 +24,9 @@ impl<T> LookupSet<T> {
     where
         S: IntoStorageKey,
     {
-        Self { element_prefix: element_prefix.into_storage_key(), el: PhantomData }
+        let element_prefix = element_prefix.into_storage_key();
+        register_prefix::<Self>(&element_prefix);
+        Self { element_prefix, el: PhantomData }
     }
 
     fn raw_element_to_storage_key(&self, element_raw: &[u8]) -> Vec<u8> {
@@ -204,4 +206,21 @@ mod tests {
             assert!(set.contains(&key));
         }
     }
+
+    #[test]
+    pub fn test_against_hash_set() {
+        test_env::setup();
+        let mut set = LookupSet::new(b"s");
+        let mut baseline = HashSet::new();
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(6);
+        for _ in 0..500 {
+            // Small key space, so that the operations often hit existing elements.
+            let key = rng.gen::<u64>() % 50;
+            match rng.gen::<u8>() % 3 {
+                0 => assert_eq!(set.insert(&key), baseline.insert(key)),
+                1 => assert_eq!(set.remove(&key), baseline.remove(&key)),
+                _ => assert_eq!(set.contains(&key), baseline.contains(&key)),
+            }
+        }
+    }
 }
@@ -0,0 +1,32 @@
+use crate::IntoStorageKey;
+
+/// A map that can be saved as the vector of its entries and restored from it, for example under a
+/// new prefix in a state migration. Migration code that only needs this can be generic over the
+/// map implementation.
+///
+/// # Examples
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::collections::{Map, TreeMap, UnorderedMap};
+///
+/// fn migrate<M: Map<String, u32>>(old: &M, prefix: Vec<u8>) -> M {
+///     M::from_pairs(prefix, old.to_vec())
+/// }
+///
+/// let mut unordered: UnorderedMap<String, u32> = UnorderedMap::new(b"u");
+/// unordered.insert(&"a".to_string(), &1);
+/// assert_eq!(migrate(&unordered, b"v".to_vec()).to_vec(), unordered.to_vec());
+///
+/// let mut tree: TreeMap<String, u32> = TreeMap::new(b"t");
+/// tree.insert(&"a".to_string(), &1);
+/// assert_eq!(migrate(&tree, b"s".to_vec()).to_vec(), tree.to_vec());
+/// ```
+pub trait Map<K, V>: Sized {
+    /// Returns all entries of the map, reading every entry from the storage.
+    fn to_vec(&self) -> Vec<(K, V)>;
+
+    /// Creates a map under the given prefix from the entries returned by [`to_vec`](Self::to_vec).
+    fn from_pairs<S>(prefix: S, pairs: Vec<(K, V)>) -> Self
+    where
+        S: IntoStorageKey;
+}
@@ -45,10 +45,10 @@ mod lookup_set;
 pub use lookup_set::LookupSet;
 
 mod vector;
-pub use vector::Vector;
+pub use vector::{IndexError, Vector};
 
 mod unordered_map;
-pub use unordered_map::UnorderedMap;
+pub use unordered_map::{UnorderedMap, ValueGuard};
 
 mod unordered_set;
 pub use unordered_set::UnorderedSet;
@@ -57,7 +57,20 @@ mod lazy_option;
 pub use lazy_option::LazyOption;
 
 mod tree_map;
-pub use tree_map::TreeMap;
+pub use tree_map::{TreeMap, TreeMapEntry};
+
+mod page;
+pub use page::Page;
+
+mod map;
+pub use map::Map;
+
+use crate::env;
+use borsh::BorshSerialize;
+use std::mem::size_of;
+
+/// The storage key of the counter used by [`unique_prefix`], it also starts the prefixes.
+const NEXT_PREFIX_KEY: &[u8] = b"__next_prefix";
 
 pub const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
 pub const ERR_ELEMENT_SERIALIZATION: &[u8] = b"Cannot serialize element with Borsh.";
@@ -70,3 +83,145 @@ pub(crate) fn append(id: &[u8], chr: u8) -> Vec<u8> {
 pub(crate) fn append_slice(id: &[u8], extra: &[u8]) -> Vec<u8> {
     [id, extra].concat()
 }
+
+#[cfg(all(feature = "debug-prefix-collision", debug_assertions))]
+thread_local! {
+    /// The prefixes of the collections created in the current execution with the type of the
+    /// collection, see [`register_prefix`].
+    static USED_PREFIXES: std::cell::RefCell<std::collections::HashMap<Vec<u8>, &'static str>> =
+        Default::default();
+}
+
+/// Panics if a collection of another type was already created with the same prefix in this
+/// execution: two collections that share a prefix overwrite each other's entries. Only checked
+/// with the `debug-prefix-collision` feature in builds with debug assertions, otherwise this does
+/// nothing.
+///
+/// Reopening a collection of the same type, including the element types, under the same prefix
+/// is allowed, for example a nested collection created from [`nested_prefix`] each time it is
+/// accessed.
+#[allow(unused_variables)]
+pub(crate) fn register_prefix<C: ?Sized>(prefix: &[u8]) {
+    #[cfg(all(feature = "debug-prefix-collision", debug_assertions))]
+    USED_PREFIXES.with(|used| {
+        let collection = std::any::type_name::<C>();
+        match used.borrow_mut().entry(prefix.to_vec()) {
+            std::collections::hash_map::Entry::Occupied(entry) if *entry.get() != collection => {
+                env::panic(
+                    format!(
+                        "The storage prefix {:?} is already used by another collection",
+                        prefix
+                    )
+                    .as_bytes(),
+                )
+            }
+            std::collections::hash_map::Entry::Occupied(_) => {}
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(collection);
+            }
+        }
+    })
+}
+
+/// Forgets the registered prefixes, called when the mocked blockchain starts a new execution.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn clear_registered_prefixes() {
+    #[cfg(all(feature = "debug-prefix-collision", debug_assertions))]
+    USED_PREFIXES.with(|used| used.borrow_mut().clear());
+}
+
+/// Returns a storage prefix for a collection that is not given one explicitly, like a collection
+/// created with `collect`. The prefixes are numbered by a counter stored under
+/// [`NEXT_PREFIX_KEY`], so the `n`-th such collection of a contract always gets the same prefix,
+/// no matter in which execution it is created.
+///
+/// Explicit prefixes, for example from a [`BorshStorageKey`](crate::BorshStorageKey) enum, are
+/// still preferable: they don't depend on the order in which the collections are created.
+///
+/// Taking a prefix writes the counter, so it aborts the call with `ProhibitedInView` in view
+/// calls.
+pub(crate) fn unique_prefix() -> Vec<u8> {
+    let id = env::storage_read(NEXT_PREFIX_KEY)
+        .map(|raw| {
+            let mut id = [0u8; size_of::<u64>()];
+            id.copy_from_slice(&raw);
+            u64::from_le_bytes(id)
+        })
+        .unwrap_or(0);
+    env::storage_write(NEXT_PREFIX_KEY, &(id + 1).to_le_bytes());
+    append_slice(NEXT_PREFIX_KEY, &id.to_le_bytes())
+}
+
+/// Returns the storage prefix of a collection nested in another collection, like the inner maps
+/// of a `LookupMap<AccountId, UnorderedMap<K, V>>`. The prefix is the prefix of the outer
+/// collection followed by the SHA-256 hash of the Borsh serialized key, so the prefixes of
+/// different keys don't collide, and the hash has a fixed length, so they are not prefixes of one
+/// another either.
+///
+/// The same outer prefix and key always give the same prefix, so the inner collection can be
+/// opened again in a later call by computing the prefix from the key. The outer prefix of a
+/// [`BorshStorageKey`](crate::BorshStorageKey) variant is given by
+/// [`into_storage_key`](crate::IntoStorageKey::into_storage_key).
+///
+/// # Examples
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::collections::{nested_prefix, LookupMap};
+/// use near_sdk::AccountId;
+///
+/// let mut balances: LookupMap<AccountId, LookupMap<String, u128>> = LookupMap::new(b"b");
+/// let owner: AccountId = "alice.near".parse().unwrap();
+/// let mut tokens = LookupMap::new(nested_prefix(b"b", &owner));
+/// tokens.insert(&"wNEAR".to_string(), &10);
+/// balances.insert(&owner, &tokens);
+///
+/// let tokens: LookupMap<String, u128> = LookupMap::new(nested_prefix(b"b", &owner));
+/// assert_eq!(tokens.get(&"wNEAR".to_string()), Some(10));
+/// ```
+pub fn nested_prefix(outer_prefix: &[u8], key: &impl BorshSerialize) -> Vec<u8> {
+    let key = key.try_to_vec().unwrap_or_else(|_| env::panic(ERR_ELEMENT_SERIALIZATION));
+    append_slice(outer_prefix, &env::sha256(&key))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockedBlockchain;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_unique_prefix() {
+        env::set_blockchain_interface(MockedBlockchain::default());
+        let first = unique_prefix();
+        let second = unique_prefix();
+        assert_ne!(first, second);
+        assert_eq!(first, [NEXT_PREFIX_KEY, &0u64.to_le_bytes()].concat());
+
+        // A restart keeps the state, so the counter continues.
+        test_env::setup();
+        let third = unique_prefix();
+        assert_ne!(third, first);
+        assert_ne!(third, second);
+
+        // The same history of the state gives the same prefixes.
+        env::set_blockchain_interface(MockedBlockchain::default());
+        assert_eq!(unique_prefix(), first);
+        assert_eq!(unique_prefix(), second);
+    }
+
+    #[test]
+    fn test_nested_prefix() {
+        test_env::setup();
+        let alice = nested_prefix(b"m", &"alice.near".to_string());
+        let bob = nested_prefix(b"m", &"bob.near".to_string());
+        assert_ne!(alice, bob);
+        assert!(alice.starts_with(b"m"));
+        assert_eq!(alice.len(), 1 + 32);
+
+        // The same pair always gives the same prefix.
+        assert_eq!(nested_prefix(b"m", &"alice.near".to_string()), alice);
+        // The same key under another outer prefix gives another prefix.
+        assert_ne!(nested_prefix(b"n", &"alice.near".to_string()), alice);
+    }
+}
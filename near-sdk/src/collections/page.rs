@@ -0,0 +1,76 @@
+//! A page of a paginated result of a view method.
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+/// A page of the elements of a collection together with the cursor to fetch the next page, meant
+/// to be returned from view methods.
+///
+/// The cursor is the index of the first element of the next page, so to read the whole collection
+/// the caller starts from `0` and passes the returned `next_cursor` to the next call until it is
+/// `None`. Since the cursor is index-based, the pages might skip or repeat elements if the
+/// collection is modified between the calls, e.g. [`UnorderedMap::remove`] moves the last element
+/// into the place of the removed one.
+///
+/// Serializes to JSON as `{"items":[...],"next_cursor":2}`.
+///
+/// # Examples
+/// ```
+/// use near_sdk::collections::Page;
+///
+/// let page = Page::from_iter(0, 2, vec![1, 2, 3].into_iter());
+/// assert_eq!(page.items, vec![1, 2]);
+/// assert_eq!(page.next_cursor, Some(2));
+///
+/// let page = Page::from_iter(2, 2, vec![3].into_iter());
+/// assert_eq!(page.items, vec![3]);
+/// assert_eq!(page.next_cursor, None);
+/// ```
+///
+/// [`UnorderedMap::remove`]: crate::collections::UnorderedMap::remove
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, BorshSerialize, BorshDeserialize)]
+pub struct Page<T> {
+    /// Elements of this page.
+    pub items: Vec<T>,
+    /// Index of the first element of the next page, or `None` if this is the last page.
+    pub next_cursor: Option<u64>,
+}
+
+impl<T> Page<T> {
+    /// Creates a page from an iterator that starts at the element with index `start`, such as
+    /// `iter().skip(start)`. At most `limit` elements are taken, and one more element is read to
+    /// find out whether there is a next page.
+    pub fn from_iter<I>(start: u64, limit: u64, iter: I) -> Self
+    where
+        I: Iterator<Item = T>,
+    {
+        let mut iter = iter.peekable();
+        let items: Vec<T> = iter.by_ref().take(limit as usize).collect();
+        let next_cursor = iter.peek().map(|_| start + items.len() as u64);
+        Self { items, next_cursor }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_iter() {
+        let elements: Vec<u32> = (0..5).collect();
+        let page = Page::from_iter(0, 2, elements.iter().copied());
+        assert_eq!(page, Page { items: vec![0, 1], next_cursor: Some(2) });
+        let page = Page::from_iter(2, 3, elements.iter().copied().skip(2));
+        assert_eq!(page, Page { items: vec![2, 3, 4], next_cursor: None });
+        let page = Page::from_iter(5, 3, elements.iter().copied().skip(5));
+        assert_eq!(page, Page { items: vec![], next_cursor: None });
+    }
+
+    #[test]
+    fn test_json() {
+        let page = Page { items: vec!["a".to_string()], next_cursor: Some(1) };
+        let json = serde_json::to_string(&page).unwrap();
+        assert_eq!(json, r#"{"items":["a"],"next_cursor":1}"#);
+        assert_eq!(serde_json::from_str::<Page<String>>(&json).unwrap(), page);
+    }
+}
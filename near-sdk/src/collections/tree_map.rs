@@ -2,7 +2,7 @@ use borsh::{BorshDeserialize, BorshSerialize};
 use std::ops::Bound;
 
 use crate::collections::LookupMap;
-use crate::collections::{append, Vector};
+use crate::collections::{append, register_prefix, Map, Vector};
 use crate::IntoStorageKey;
 
 /// TreeMap based on AVL-tree
@@ -49,6 +49,7 @@ where
         S: IntoStorageKey,
     {
         let prefix = prefix.into_storage_key();
+        register_prefix::<Self>(&prefix);
         Self {
             root: 0,
             val: LookupMap::new(append(&prefix, b'v')),
@@ -99,6 +100,26 @@ where
         self.val.insert(&key, &val)
     }
 
+    /// Gets the entry of the given key for in-place manipulation. The value is read once when the
+    /// entry is created, so `and_modify` and `or_insert` don't look the key up again, and a new key
+    /// descends the tree only once when it is inserted.
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::collections::TreeMap;
+    ///
+    /// let mut counts: TreeMap<String, u64> = TreeMap::new(b"c");
+    /// for word in ["b", "a", "b"].iter() {
+    ///     counts.entry(word.to_string()).and_modify(|count| *count += 1).or_insert(1);
+    /// }
+    /// assert_eq!(counts.to_vec(), vec![("a".to_string(), 1), ("b".to_string(), 2)]);
+    /// ```
+    pub fn entry(&mut self, key: K) -> TreeMapEntry<'_, K, V> {
+        let value = self.val.get(&key);
+        TreeMapEntry { map: self, key, value }
+    }
+
     pub fn remove(&mut self, key: &K) -> Option<V> {
         if self.contains_key(&key) {
             self.root = self.do_remove(&key);
@@ -580,6 +601,76 @@ where
     }
 }
 
+/// An entry of a [`TreeMap`] returned by [`TreeMap::entry`]. Unlike the entries of `std`
+/// collections, every method that changes the value writes it to the storage right away.
+pub struct TreeMapEntry<'a, K, V> {
+    map: &'a mut TreeMap<K, V>,
+    key: K,
+    value: Option<V>,
+}
+
+impl<'a, K, V> TreeMapEntry<'a, K, V>
+where
+    K: Ord + Clone + BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    /// Returns the key of the entry.
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Calls `f` on the value and saves the result if the key is in the map.
+    pub fn and_modify<F: FnOnce(&mut V)>(mut self, f: F) -> Self {
+        if let Some(value) = &mut self.value {
+            f(value);
+            self.map.val.insert(&self.key, value);
+        }
+        self
+    }
+
+    /// Inserts `default` if the key is not in the map and returns the value of the entry.
+    pub fn or_insert(self, default: V) -> V {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `f` if the key is not in the map and returns the value of the entry.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, f: F) -> V {
+        let TreeMapEntry { map, key, value } = self;
+        match value {
+            Some(value) => value,
+            None => {
+                let value = f();
+                map.root = map.insert_at(map.root, map.len(), &key);
+                map.val.insert(&key, &value);
+                value
+            }
+        }
+    }
+}
+
+impl<K, V> Map<K, V> for TreeMap<K, V>
+where
+    K: Ord + Clone + BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn to_vec(&self) -> Vec<(K, V)> {
+        TreeMap::to_vec(self)
+    }
+
+    /// Inserts the pairs one by one, a pair with a key that appeared before replaces the earlier
+    /// value.
+    fn from_pairs<S>(prefix: S, pairs: Vec<(K, V)>) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut map = Self::new(prefix);
+        for (key, value) in pairs {
+            map.insert(&key, &value);
+        }
+        map
+    }
+}
+
 impl<'a, K, V> IntoIterator for &'a TreeMap<K, V>
 where
     K: Ord + Clone + BorshSerialize + BorshDeserialize,
@@ -946,6 +1037,39 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_entry() {
+        test_env::setup_free();
+        let mut map: TreeMap<u32, u32> = TreeMap::new(next_trie_id());
+        let mut baseline: BTreeMap<u32, u32> = BTreeMap::new();
+        let input = random(500);
+        for x in &input {
+            let mut modified = 0;
+            let mut inserted = 0;
+            let value = map
+                .entry(*x)
+                .and_modify(|v| {
+                    modified += 1;
+                    *v += 1;
+                })
+                .or_insert_with(|| {
+                    inserted += 1;
+                    0
+                });
+            assert_eq!(modified + inserted, 1);
+            let expected = *baseline.entry(*x).and_modify(|v| *v += 1).or_insert(0);
+            assert_eq!(value, expected);
+        }
+        assert_eq!(map.to_vec(), baseline.into_iter().collect::<Vec<_>>());
+        assert!(height(&map) <= max_tree_height(map.len()));
+
+        assert_eq!(*map.entry(2000).key(), 2000);
+        assert_eq!(map.entry(2000).or_insert(7), 7);
+        assert_eq!(map.entry(2000).or_insert(8), 7);
+        assert_eq!(map.get(&2000), Some(7));
+        map.clear();
+    }
+
     #[test]
     fn test_min() {
         test_env::setup();
@@ -1837,4 +1961,15 @@ mod tests {
 
         QuickCheck::new().tests(300).quickcheck(prop as Prop);
     }
+
+    #[test]
+    fn test_from_pairs() {
+        test_env::setup();
+        let mut map: TreeMap<u32, u32> = TreeMap::new(next_trie_id());
+        map.insert(&2, &20);
+        map.insert(&1, &10);
+        let restored: TreeMap<u32, u32> = Map::from_pairs(next_trie_id(), Map::to_vec(&map));
+        assert_eq!(restored.to_vec(), vec![(1, 10), (2, 20)]);
+        assert_eq!(restored.get(&2), Some(20));
+    }
 }
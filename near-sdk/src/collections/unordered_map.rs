@@ -1,12 +1,16 @@
 //! A map implemented on a trie. Unlike `std::collections::HashMap` the keys in this map are not
 //! hashed but are instead serialized.
-use crate::collections::{append, append_slice, Vector};
+use crate::collections::{
+    append, append_slice, register_prefix, unique_prefix, vector, Map, Page, Vector,
+};
 use crate::{env, IntoStorageKey};
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::mem::size_of;
+use std::ops::{Add, Deref, DerefMut};
 
 const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
 const ERR_KEY_SERIALIZATION: &[u8] = b"Cannot serialize key with Borsh";
+const ERR_KEY_DESERIALIZATION: &[u8] = b"Cannot deserialize key with Borsh";
 const ERR_VALUE_DESERIALIZATION: &[u8] = b"Cannot deserialize value with Borsh";
 const ERR_VALUE_SERIALIZATION: &[u8] = b"Cannot serialize value with Borsh";
 
@@ -47,6 +51,7 @@ impl<K, V> UnorderedMap<K, V> {
         S: IntoStorageKey,
     {
         let prefix = prefix.into_storage_key();
+        register_prefix::<Self>(&prefix);
         let key_index_prefix = append(&prefix, b'i');
         let index_key_id = append(&prefix, b'k');
         let index_value_id = append(&prefix, b'v');
@@ -157,6 +162,13 @@ where
         }
     }
 
+    fn deserialize_key(raw_key: &[u8]) -> K {
+        match K::try_from_slice(raw_key) {
+            Ok(x) => x,
+            Err(_) => env::panic(ERR_KEY_DESERIALIZATION),
+        }
+    }
+
     fn deserialize_value(raw_value: &[u8]) -> V {
         match V::try_from_slice(&raw_value) {
             Ok(x) => x,
@@ -176,6 +188,146 @@ where
         self.get_raw(&Self::serialize_key(key)).map(|value_raw| Self::deserialize_value(&value_raw))
     }
 
+    /// Returns `true` if the map contains the key. Unlike `get(..).is_some()`, this only checks
+    /// that the index of the key is in the storage, without reading or deserializing the value.
+    pub fn contains_key(&self, key: &K) -> bool {
+        env::storage_has_key(&self.raw_key_to_index_lookup(&Self::serialize_key(key)))
+    }
+
+    /// Updates the value at `key` in place: reads the value, passes it to `f` and writes the
+    /// modified value back. Returns `false` without calling `f` or writing anything if the key is
+    /// not in the map, so unlike `get` followed by `insert` it can't insert a missing key by
+    /// accident.
+    ///
+    /// Reads the index of the key and the value once, and writes the value once.
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::collections::UnorderedMap;
+    ///
+    /// let mut balances: UnorderedMap<String, u128> = UnorderedMap::new(b"b");
+    /// balances.insert(&"alice".to_string(), &10);
+    ///
+    /// assert!(balances.modify(&"alice".to_string(), |balance| *balance += 5));
+    /// assert_eq!(balances.get(&"alice".to_string()), Some(15));
+    /// assert!(!balances.modify(&"bob".to_string(), |balance| *balance += 5));
+    /// assert_eq!(balances.get(&"bob".to_string()), None);
+    /// ```
+    pub fn modify<F: FnOnce(&mut V)>(&mut self, key: &K, f: F) -> bool {
+        let index = match self.get_index_raw(&Self::serialize_key(key)) {
+            Some(index) => index,
+            None => return false,
+        };
+        let mut value = match self.values.get_raw(index) {
+            Some(value_raw) => Self::deserialize_value(&value_raw),
+            None => env::panic(ERR_INCONSISTENT_STATE),
+        };
+        f(&mut value);
+        self.values.replace_raw(index, &Self::serialize_value(&value));
+        true
+    }
+
+    /// Returns a guard that dereferences to the value at `key`, or `None` if the key is not in the
+    /// map. If the value is accessed mutably through the guard, it is written back when the
+    /// guard is dropped, otherwise nothing is written.
+    ///
+    /// The index of the key is looked up once, and the value is read and written once.
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::collections::UnorderedMap;
+    ///
+    /// let mut balances: UnorderedMap<String, u128> = UnorderedMap::new(b"b");
+    /// balances.insert(&"alice".to_string(), &10);
+    ///
+    /// if let Some(mut balance) = balances.get_mut(&"alice".to_string()) {
+    ///     *balance += 5;
+    /// }
+    /// assert_eq!(balances.get(&"alice".to_string()), Some(15));
+    /// assert!(balances.get_mut(&"bob".to_string()).is_none());
+    /// ```
+    pub fn get_mut(&mut self, key: &K) -> Option<ValueGuard<'_, V>> {
+        let index = self.get_index_raw(&Self::serialize_key(key))?;
+        let value = match self.values.get_raw(index) {
+            Some(value_raw) => Self::deserialize_value(&value_raw),
+            None => env::panic(ERR_INCONSISTENT_STATE),
+        };
+        Some(ValueGuard { values: &mut self.values, index, value, is_modified: false })
+    }
+
+    /// Returns the value at `key`, or inserts the value returned by `default` and returns it if the
+    /// key is not in the map. `default` is only called when the key is missing.
+    ///
+    /// The index of the key is looked up once, like in [`increment`](Self::increment).
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::collections::UnorderedMap;
+    ///
+    /// let mut counters: UnorderedMap<String, u64> = UnorderedMap::new(b"c");
+    /// assert_eq!(counters.get_or_insert_with(&"alice".to_string(), || 0), 0);
+    /// counters.insert(&"alice".to_string(), &3);
+    /// assert_eq!(counters.get_or_insert_with(&"alice".to_string(), || 0), 3);
+    /// ```
+    pub fn get_or_insert_with<F: FnOnce() -> V>(&mut self, key: &K, default: F) -> V {
+        let key_raw = Self::serialize_key(key);
+        let index_lookup = self.raw_key_to_index_lookup(&key_raw);
+        match env::storage_read(&index_lookup) {
+            Some(index_raw) => match self.values.get_raw(Self::deserialize_index(&index_raw)) {
+                Some(value_raw) => Self::deserialize_value(&value_raw),
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            },
+            None => {
+                let value = default();
+                let next_index_raw = Self::serialize_index(self.len());
+                env::storage_write(&index_lookup, &next_index_raw);
+                self.keys.push_raw(&key_raw);
+                self.values.push_raw(&Self::serialize_value(&value));
+                value
+            }
+        }
+    }
+
+    /// Retains only the entries for which `f` returns `true`, like `HashMap::retain`.
+    ///
+    /// Every entry is read from the storage. The entries are visited from the last one to the
+    /// first, so an entry that is swapped into the place of a removed one has always been visited
+    /// already, and the order of the retained entries may change.
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, mut f: F) {
+        for index in (0..self.len()).rev() {
+            let (key_raw, value_raw) = match (self.keys.get_raw(index), self.values.get_raw(index))
+            {
+                (Some(key_raw), Some(value_raw)) => (key_raw, value_raw),
+                _ => env::panic(ERR_INCONSISTENT_STATE),
+            };
+            if !f(&Self::deserialize_key(&key_raw), &Self::deserialize_value(&value_raw)) {
+                self.remove_raw(&key_raw);
+            }
+        }
+    }
+
+    /// Applies `f` to every value of the map and writes the values back in place, for example to
+    /// apply interest to every balance.
+    ///
+    /// The keys don't change, so neither the keys nor their index are read or written: every
+    /// value is read and written once.
+    pub fn for_each_value_mut<F: FnMut(&mut V)>(&mut self, mut f: F) {
+        if self.keys.len() != self.values.len() {
+            env::panic(ERR_INCONSISTENT_STATE)
+        }
+        for index in 0..self.values.len() {
+            let mut value = match self.values.get_raw(index) {
+                Some(value_raw) => Self::deserialize_value(&value_raw),
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            };
+            f(&mut value);
+            self.values.replace_raw(index, &Self::serialize_value(&value));
+        }
+    }
+
     /// Removes a key from the map, returning the value at the key if the key was previously in the
     /// map.
     pub fn remove(&mut self, key: &K) -> Option<V> {
@@ -183,6 +335,60 @@ where
             .map(|value_raw| Self::deserialize_value(&value_raw))
     }
 
+    /// Renames the key `old` to `new`, keeping the value and its position in the map. Returns
+    /// `false` and does nothing if `old` is not in the map or `new` is already in the map.
+    pub fn replace_key(&mut self, old: &K, new: &K) -> bool {
+        let old_raw = Self::serialize_key(old);
+        let new_raw = Self::serialize_key(new);
+        let index_raw = match env::storage_read(&self.raw_key_to_index_lookup(&old_raw)) {
+            Some(x) => x,
+            None => return false,
+        };
+        if self.get_index_raw(&new_raw).is_some() {
+            return false;
+        }
+        env::storage_remove(&self.raw_key_to_index_lookup(&old_raw));
+        env::storage_write(&self.raw_key_to_index_lookup(&new_raw), &index_raw);
+        self.keys.replace_raw(Self::deserialize_index(&index_raw), &new_raw);
+        true
+    }
+
+    /// Removes all given keys from the map, returning the number of keys that were present.
+    ///
+    /// Unlike calling `remove` for every key, the entries are removed starting from the highest
+    /// index, so that removed entries are never swapped into the place of other removed entries.
+    /// This way removing a contiguous tail of the map does not rewrite any index lookups, and in
+    /// general every remaining entry is moved at most once.
+    pub fn remove_all<IT: IntoIterator<Item = K>>(&mut self, keys: IT) -> u64 {
+        let mut indices: Vec<u64> = keys
+            .into_iter()
+            .filter_map(|key| self.get_index_raw(&Self::serialize_key(&key)))
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+        for &index in indices.iter().rev() {
+            let key_raw = match self.keys.get_raw(index) {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            };
+            env::storage_remove(&self.raw_key_to_index_lookup(&key_raw));
+            // All removed entries with higher indices are already gone, so the last element is
+            // always one that stays in the map.
+            let last_index = self.len() - 1;
+            if index != last_index {
+                let last_key_raw = match self.keys.get_raw(last_index) {
+                    Some(x) => x,
+                    None => env::panic(ERR_INCONSISTENT_STATE),
+                };
+                let last_lookup_key = self.raw_key_to_index_lookup(&last_key_raw);
+                env::storage_write(&last_lookup_key, &Self::serialize_index(index));
+            }
+            self.keys.swap_remove_raw(index);
+            self.values.swap_remove_raw(index);
+        }
+        indices.len() as u64
+    }
+
     /// Inserts a key-value pair into the map.
     /// If the map did not have this key present, `None` is returned. Otherwise returns
     /// a value. Note, the keys that have the same hash value are undistinguished by
@@ -192,6 +398,24 @@ where
             .map(|value_raw| Self::deserialize_value(&value_raw))
     }
 
+    /// Inserts a key-value pair into the map like [`insert`](Self::insert), but overwrites the
+    /// value that was previously stored at the key without reading it. Use it on write-heavy
+    /// paths that don't need the old value.
+    pub fn set(&mut self, key: &K, value: &V) {
+        let key_raw = Self::serialize_key(key);
+        let value_raw = Self::serialize_value(value);
+        let index_lookup = self.raw_key_to_index_lookup(&key_raw);
+        match env::storage_read(&index_lookup) {
+            Some(index_raw) => self.values.set_raw(Self::deserialize_index(&index_raw), &value_raw),
+            None => {
+                let next_index_raw = Self::serialize_index(self.len());
+                env::storage_write(&index_lookup, &next_index_raw);
+                self.keys.push_raw(&key_raw);
+                self.values.push_raw(&value_raw);
+            }
+        }
+    }
+
     /// Clears the map, removing all elements.
     pub fn clear(&mut self) {
         for raw_key in self.keys.iter_raw() {
@@ -202,32 +426,184 @@ where
         self.values.clear();
     }
 
+    /// Removes all elements from the map, returning them as an iterator.
+    ///
+    /// Entries are yielded starting from the most recently inserted one, and every entry is
+    /// removed from the trie at the moment it is yielded. If the iterator is dropped before it is
+    /// fully consumed, the entries that were not yet yielded remain in the map.
+    pub fn drain(&mut self) -> impl Iterator<Item = (K, V)> + '_ {
+        std::iter::from_fn(move || {
+            let key_raw = self.keys.pop_raw()?;
+            let value_raw = match self.values.pop_raw() {
+                Some(x) => x,
+                None => env::panic(ERR_INCONSISTENT_STATE),
+            };
+            let index_lookup = self.raw_key_to_index_lookup(&key_raw);
+            env::storage_remove(&index_lookup);
+            Some((Self::deserialize_key(&key_raw), Self::deserialize_value(&value_raw)))
+        })
+    }
+
     /// Copies elements into an `std::vec::Vec`.
     pub fn to_vec(&self) -> std::vec::Vec<(K, V)> {
         self.iter().collect()
     }
 
     /// An iterator visiting all keys. The iterator element type is `K`.
-    pub fn keys(&self) -> impl Iterator<Item = K> + '_ {
+    pub fn keys(&self) -> impl DoubleEndedIterator<Item = K> + ExactSizeIterator + '_ {
         self.keys.iter()
     }
 
     /// An iterator visiting all values. The iterator element type is `V`.
-    pub fn values(&self) -> impl Iterator<Item = V> + '_ {
+    pub fn values(&self) -> impl DoubleEndedIterator<Item = V> + ExactSizeIterator + '_ {
         self.values.iter()
     }
 
     /// Iterate over deserialized keys and values.
-    pub fn iter(&self) -> impl Iterator<Item = (K, V)> + '_ {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (K, V)> + ExactSizeIterator + '_ {
         self.keys.iter().zip(self.values.iter())
     }
 
+    /// Iterate over deserialized keys and values in reversed storage order, from the entry with the
+    /// last index to the first. This is the reversed insertion order only as long as nothing was
+    /// removed: [`remove`](Self::remove) moves the last entry into the place of the removed one.
+    pub fn iter_rev(&self) -> impl Iterator<Item = (K, V)> + ExactSizeIterator + '_ {
+        self.iter().rev()
+    }
+
+    /// Iterate over deserialized keys and values starting from the entry with index
+    /// `from_index`, in the same order as [`iter`](Self::iter). The skipped entries are not read
+    /// from the storage. If `from_index` is out of range, the iterator is empty.
+    pub fn iter_from_index(
+        &self,
+        from_index: u64,
+    ) -> impl DoubleEndedIterator<Item = (K, V)> + ExactSizeIterator + '_ {
+        self.keys.iter_from_index(from_index).zip(self.values.iter_from_index(from_index))
+    }
+
+    /// Copies at most `limit` entries starting from the entry with index `from_index` into a
+    /// `Vec`. Returns an empty `Vec` if `from_index` is out of range.
+    pub fn to_vec_paged(&self, from_index: u64, limit: u64) -> std::vec::Vec<(K, V)> {
+        self.iter_from_index(from_index).take(limit as usize).collect()
+    }
+
+    /// Returns a page of at most `limit` entries starting from the entry with index `start`, with
+    /// the entries in the same order as [`iter`](Self::iter). Only the entries of the page are read
+    /// from the storage. See [`Page`] for the cursor semantics.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::collections::UnorderedMap;
+    ///
+    /// let mut map: UnorderedMap<String, u32> = UnorderedMap::new(b"m");
+    /// map.insert(&"a".to_string(), &1);
+    /// map.insert(&"b".to_string(), &2);
+    ///
+    /// let page = map.page(0, 1);
+    /// assert_eq!(page.items, vec![("a".to_string(), 1)]);
+    /// let page = map.page(page.next_cursor.unwrap(), 1);
+    /// assert_eq!(page.items, vec![("b".to_string(), 2)]);
+    /// assert_eq!(page.next_cursor, None);
+    /// ```
+    pub fn page(&self, start: u64, limit: u64) -> Page<(K, V)> {
+        Page::from_iter(start, limit, self.iter_from_index(start))
+    }
+
     pub fn extend<IT: IntoIterator<Item = (K, V)>>(&mut self, iter: IT) {
         for (el_key, el_value) in iter {
             self.insert(&el_key, &el_value);
         }
     }
 
+    /// Creates a map under the given prefix and inserts the entries of `map`. Writing to the trie
+    /// needs the blockchain environment, so `UnorderedMap` can't implement `Deserialize`. Instead,
+    /// deserialize the JSON object sent by the client into a `HashMap` and pass it here.
+    ///
+    /// The entries are stored in the iteration order of the `HashMap`, which is the order
+    /// [`iter`](Self::iter) returns them in.
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::collections::UnorderedMap;
+    /// use std::collections::HashMap;
+    ///
+    /// let initial: HashMap<String, u32> = near_sdk::serde_json::from_str(r#"{"a":1}"#).unwrap();
+    /// let map = UnorderedMap::from_hash_map(b"m", initial);
+    /// assert_eq!(map.get(&"a".to_string()), Some(1));
+    /// ```
+    pub fn from_hash_map<S>(prefix: S, map: std::collections::HashMap<K, V>) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut result = Self::new(prefix);
+        result.extend(map);
+        result
+    }
+
+    /// Creates a map under the given prefix from the entries returned by [`to_vec`](Self::to_vec),
+    /// in the same order. Together they save a map and restore it, for example under a new prefix
+    /// in a state migration.
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::collections::UnorderedMap;
+    ///
+    /// let mut old: UnorderedMap<String, u32> = UnorderedMap::new(b"o");
+    /// old.insert(&"a".to_string(), &1);
+    /// let new = UnorderedMap::from_pairs(b"n", old.to_vec());
+    /// assert_eq!(new.to_vec(), old.to_vec());
+    /// ```
+    pub fn from_pairs<S>(prefix: S, pairs: std::vec::Vec<(K, V)>) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        let mut result = Self::new(prefix);
+        result.extend(pairs);
+        result
+    }
+
+    /// Returns all keys of the map sorted in ascending order.
+    ///
+    /// This reads every key from the storage and sorts them, which is `O(n log n)`. Only use it for
+    /// maps whose size is bounded, otherwise the call can run out of gas as the map grows.
+    pub fn keys_sorted(&self) -> Vec<K>
+    where
+        K: Ord,
+    {
+        let mut keys: Vec<K> = self.keys().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Returns all values of the map sorted in ascending order.
+    ///
+    /// This reads every value from the storage and sorts them, which is `O(n log n)`. Only use it
+    /// for maps whose size is bounded, otherwise the call can run out of gas as the map grows.
+    pub fn values_sorted(&self) -> Vec<V>
+    where
+        V: Ord,
+    {
+        let mut values: Vec<V> = self.values().collect();
+        values.sort();
+        values
+    }
+
+    /// Returns all entries of the map sorted in ascending order of their keys.
+    ///
+    /// Like [`keys_sorted`](Self::keys_sorted), this reads the whole map and sorts it in memory,
+    /// which is `O(n log n)`. It suits occasional sorted output of small maps, use a
+    /// [`TreeMap`](crate::collections::TreeMap) for maps that grow large.
+    pub fn iter_sorted_by_key(&self) -> Vec<(K, V)>
+    where
+        K: Ord,
+    {
+        let mut entries: Vec<(K, V)> = self.iter().collect();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries
+    }
+
     /// Returns a view of keys as a vector.
     /// It's sometimes useful to have random access to the keys.
     pub fn keys_as_vector(&self) -> &Vector<K> {
@@ -241,10 +617,146 @@ where
     }
 }
 
+impl<K, V> UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize + Add<Output = V> + Default + Copy,
+{
+    /// Adds `by` to the value corresponding to the key, inserting `by` if the key is not present.
+    /// Returns the value after the increment. This is the same as a [`get`](Self::get) followed
+    /// by an [`insert`](Self::insert), but only looks up the key index once.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::collections::UnorderedMap;
+    ///
+    /// let mut votes: UnorderedMap<String, u32> = UnorderedMap::new(b"v");
+    /// votes.increment(&"alice".to_string(), 1);
+    /// assert_eq!(votes.increment(&"alice".to_string(), 2), 3);
+    /// assert_eq!(votes.get(&"alice".to_string()), Some(3));
+    /// ```
+    pub fn increment(&mut self, key: &K, by: V) -> V {
+        let key_raw = Self::serialize_key(key);
+        let index_lookup = self.raw_key_to_index_lookup(&key_raw);
+        match env::storage_read(&index_lookup) {
+            Some(index_raw) => {
+                let index = Self::deserialize_index(&index_raw);
+                let value_raw = match self.values.get_raw(index) {
+                    Some(x) => x,
+                    None => env::panic(ERR_INCONSISTENT_STATE),
+                };
+                let value = Self::deserialize_value(&value_raw) + by;
+                self.values.replace_raw(index, &Self::serialize_value(&value));
+                value
+            }
+            None => {
+                let value = V::default() + by;
+                let next_index_raw = Self::serialize_index(self.len());
+                env::storage_write(&index_lookup, &next_index_raw);
+                self.keys.push_raw(&key_raw);
+                self.values.push_raw(&Self::serialize_value(&value));
+                value
+            }
+        }
+    }
+}
+
+impl<'a, K, V> IntoIterator for &'a UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    type Item = (K, V);
+    type IntoIter = std::iter::Zip<vector::Iter<'a, K>, vector::Iter<'a, V>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.keys.into_iter().zip(&self.values)
+    }
+}
+
+/// Collects the pairs into a new map. Like [`UnorderedMap::extend`], a pair with a key that
+/// appeared before replaces the earlier value.
+///
+/// The map is stored under a prefix numbered by a counter in the contract storage, so this only
+/// works where the blockchain environment is available: during a contract call or in a test set
+/// up with the mocked blockchain. Relying on the numbering is discouraged, to store the map in the
+/// contract state under a known prefix, create it with [`UnorderedMap::new`] and
+/// [`extend`](UnorderedMap::extend) it instead.
+///
+/// Numbering the prefix writes the counter to the storage, so collecting into a map aborts view
+/// calls with `ProhibitedInView`, even for an empty iterator. View methods should collect into a
+/// `Vec` or a `std` collection instead.
+impl<K, V> std::iter::FromIterator<(K, V)> for UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn from_iter<IT: IntoIterator<Item = (K, V)>>(iter: IT) -> Self {
+        let mut map = UnorderedMap::new(unique_prefix());
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K, V> Map<K, V> for UnorderedMap<K, V>
+where
+    K: BorshSerialize + BorshDeserialize,
+    V: BorshSerialize + BorshDeserialize,
+{
+    fn to_vec(&self) -> Vec<(K, V)> {
+        UnorderedMap::to_vec(self)
+    }
+
+    fn from_pairs<S>(prefix: S, pairs: Vec<(K, V)>) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        UnorderedMap::from_pairs(prefix, pairs)
+    }
+}
+
+/// A value of an [`UnorderedMap`] returned by [`UnorderedMap::get_mut`]. The value is written
+/// back to the map when the guard is dropped if it was accessed mutably, even if it was not
+/// actually changed.
+pub struct ValueGuard<'a, V: BorshSerialize> {
+    values: &'a mut Vector<V>,
+    index: u64,
+    value: V,
+    is_modified: bool,
+}
+
+impl<'a, V: BorshSerialize> Deref for ValueGuard<'a, V> {
+    type Target = V;
+
+    fn deref(&self) -> &V {
+        &self.value
+    }
+}
+
+impl<'a, V: BorshSerialize> DerefMut for ValueGuard<'a, V> {
+    fn deref_mut(&mut self) -> &mut V {
+        self.is_modified = true;
+        &mut self.value
+    }
+}
+
+impl<'a, V: BorshSerialize> Drop for ValueGuard<'a, V> {
+    fn drop(&mut self) {
+        if self.is_modified {
+            let value_raw = match self.value.try_to_vec() {
+                Ok(x) => x,
+                Err(_) => env::panic(ERR_VALUE_SERIALIZATION),
+            };
+            self.values.replace_raw(self.index, &value_raw);
+        }
+    }
+}
+
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
     use crate::collections::UnorderedMap;
+    use crate::env;
     use crate::test_utils::test_env;
     use rand::seq::SliceRandom;
     use rand::{Rng, SeedableRng};
@@ -302,6 +814,216 @@ mod tests {
         assert_eq!(actual_insert_value2, None);
     }
 
+    #[test]
+    pub fn test_replace_key() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        for key in 0..5u64 {
+            map.insert(&key, &(key * 10));
+        }
+        assert!(map.replace_key(&2, &7));
+        assert_eq!(map.get(&2), None);
+        assert_eq!(map.get(&7), Some(20));
+        assert_eq!(map.to_vec(), vec![(0, 0), (1, 10), (7, 20), (3, 30), (4, 40)]);
+
+        // Missing old key or existing new key.
+        assert!(!map.replace_key(&2, &8));
+        assert!(!map.replace_key(&7, &3));
+        assert!(!map.replace_key(&7, &7));
+        assert_eq!(map.to_vec(), vec![(0, 0), (1, 10), (7, 20), (3, 30), (4, 40)]);
+
+        // The renamed key can be removed as usual.
+        assert_eq!(map.remove(&7), Some(20));
+        assert_eq!(map.to_vec(), vec![(0, 0), (1, 10), (4, 40), (3, 30)]);
+    }
+
+    #[test]
+    pub fn test_modify() {
+        test_env::setup();
+        let mut map: UnorderedMap<u64, Vec<u64>> = UnorderedMap::new(b"m");
+        map.insert(&1, &vec![1]);
+        map.insert(&2, &vec![2]);
+
+        assert!(map.modify(&1, |value| value.push(10)));
+        assert!(map.modify(&1, |value| value.push(20)));
+        assert_eq!(map.get(&1), Some(vec![1, 10, 20]));
+        assert_eq!(map.get(&2), Some(vec![2]));
+        assert_eq!(map.to_vec(), vec![(1, vec![1, 10, 20]), (2, vec![2])]);
+
+        let storage_usage = env::storage_usage();
+        let mut called = false;
+        assert!(!map.modify(&3, |_| called = true));
+        assert!(!called);
+        assert_eq!(map.get(&3), None);
+        assert_eq!(map.len(), 2);
+        assert_eq!(env::storage_usage(), storage_usage);
+    }
+
+    #[test]
+    pub fn test_for_each_value_mut() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        for key in 0..20u64 {
+            map.insert(&key, &(key * 3));
+        }
+        map.remove(&5);
+        let keys: Vec<u64> = map.keys().collect();
+
+        map.for_each_value_mut(|value| *value *= 2);
+        assert_eq!(map.keys().collect::<Vec<_>>(), keys);
+        for key in keys.iter() {
+            assert_eq!(map.get(key), Some(key * 6));
+        }
+        assert_eq!(map.get(&5), None);
+        assert_eq!(map.len(), 19);
+    }
+
+    #[test]
+    #[should_panic(expected = "The collection is an inconsistent state")]
+    pub fn test_for_each_value_mut_inconsistent() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        map.insert(&1u64, &1u64);
+        map.insert(&2u64, &2u64);
+        // Drop the last value, so there are more keys than values.
+        map.values.pop_raw();
+        map.for_each_value_mut(|value| *value += 1);
+    }
+
+    #[test]
+    pub fn test_get_mut() {
+        test_env::setup();
+        let mut map: UnorderedMap<u64, Vec<u64>> = UnorderedMap::new(b"m");
+        map.insert(&1, &vec![1]);
+        map.insert(&2, &vec![2]);
+
+        {
+            let mut value = map.get_mut(&1).unwrap();
+            value.push(10);
+            value.push(20);
+            assert_eq!(*value, vec![1, 10, 20]);
+        }
+        assert_eq!(map.get(&1), Some(vec![1, 10, 20]));
+        assert_eq!(map.to_vec(), vec![(1, vec![1, 10, 20]), (2, vec![2])]);
+        assert!(map.get_mut(&3).is_none());
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    pub fn test_get_mut_unmodified() {
+        test_env::setup();
+        let mut map: UnorderedMap<u64, u64> = UnorderedMap::new(b"m");
+        map.insert(&1, &1);
+        // The mocked host charges gas for every call, so the used gas shows whether the guard
+        // writes the value back.
+        let used_gas_cost = {
+            let before = env::used_gas();
+            env::used_gas().0 - before.0
+        };
+
+        let value = map.get_mut(&1).unwrap();
+        assert_eq!(*value, 1);
+        let before = env::used_gas();
+        drop(value);
+        assert_eq!(env::used_gas().0 - before.0, used_gas_cost);
+
+        let mut value = map.get_mut(&1).unwrap();
+        *value += 1;
+        let before = env::used_gas();
+        drop(value);
+        assert!(env::used_gas().0 - before.0 > used_gas_cost);
+        assert_eq!(map.get(&1), Some(2));
+    }
+
+    #[test]
+    pub fn test_retain() {
+        test_env::setup_free();
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(11);
+        for round in 0..5u8 {
+            let mut map = UnorderedMap::new(vec![b'm', round]);
+            let mut baseline = HashMap::new();
+            for _ in 0..rng.gen_range(0, 100) {
+                let key = rng.gen::<u64>() % 200;
+                let value = rng.gen::<u64>();
+                map.insert(&key, &value);
+                baseline.insert(key, value);
+            }
+
+            map.retain(|key, _| key % 2 == 0);
+            baseline.retain(|key, _| key % 2 == 0);
+
+            assert_eq!(map.len(), baseline.len() as u64);
+            assert_eq!(HashMap::from_iter(map.iter()), baseline);
+            for key in 0..200 {
+                assert_eq!(map.get(&key), baseline.get(&key).copied());
+            }
+            // The index lookups are still consistent, so the removal of every key works.
+            for key in baseline.keys() {
+                assert!(map.remove(key).is_some());
+            }
+            assert!(map.is_empty());
+        }
+    }
+
+    #[test]
+    pub fn test_retain_by_value() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        map.extend((0..10u64).map(|key| (key, key * 10)));
+        map.retain(|_, value| *value >= 50);
+        assert_eq!(map.keys_sorted(), (5..10).collect::<Vec<u64>>());
+        map.retain(|_, _| false);
+        assert!(map.is_empty());
+        assert_eq!(map.to_vec(), vec![]);
+    }
+
+    #[test]
+    pub fn test_remove_all() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut naive = UnorderedMap::new(b"n");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(5);
+        for _ in 0..10 {
+            for _ in 0..rng.gen::<u64>() % 20 {
+                let key = rng.gen::<u64>() % 40;
+                let value = rng.gen::<u64>();
+                map.insert(&key, &value);
+                naive.insert(&key, &value);
+            }
+            // Keys may repeat and may be missing from the map.
+            let keys: Vec<u64> =
+                (0..rng.gen::<u64>() % 20).map(|_| rng.gen::<u64>() % 50).collect();
+            let mut expected = 0;
+            for key in keys.iter() {
+                if naive.remove(key).is_some() {
+                    expected += 1;
+                }
+            }
+            assert_eq!(map.remove_all(keys), expected);
+            assert_eq!(map.len(), naive.len());
+            for (index, key) in map.keys().enumerate() {
+                assert_eq!(
+                    map.get_index_raw(&UnorderedMap::<u64, u64>::serialize_key(&key)),
+                    Some(index as u64)
+                );
+            }
+            let actual: HashMap<u64, u64> = map.iter().collect();
+            let expected: HashMap<u64, u64> = naive.iter().collect();
+            assert_eq!(actual, expected);
+        }
+    }
+
+    #[test]
+    pub fn test_remove_all_tail() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        for key in 0..10u64 {
+            map.insert(&key, &key);
+        }
+        assert_eq!(map.remove_all(5..20), 5);
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec![0, 1, 2, 3, 4]);
+    }
+
     #[test]
     pub fn test_insert_override_remove() {
         test_env::setup();
@@ -348,6 +1070,27 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_contains_key() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        map.insert(&1u64, &10u64);
+        map.insert(&2u64, &20u64);
+        assert!(map.contains_key(&1));
+        assert!(!map.contains_key(&3));
+
+        // Remove the value of key 1 behind the map's back: `get` would panic on the inconsistent
+        // state, `contains_key` doesn't notice because it never reads the value.
+        let mut value_slot = b"mv".to_vec();
+        value_slot.extend_from_slice(&0u64.to_le_bytes());
+        assert!(env::storage_remove(&value_slot));
+        assert!(map.contains_key(&1));
+        assert!(map.contains_key(&2));
+
+        map.remove(&2);
+        assert!(!map.contains_key(&2));
+    }
+
     #[test]
     pub fn test_to_vec() {
         test_env::setup();
@@ -364,6 +1107,33 @@ mod tests {
         assert_eq!(actual, key_to_value);
     }
 
+    #[test]
+    pub fn test_set() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        map.set(&1u64, &vec![0u8; 1000]);
+        map.set(&2u64, &vec![0u8; 1000]);
+        assert_eq!(map.len(), 2);
+
+        let gas_of = |f: &mut dyn FnMut()| {
+            let before = env::used_gas();
+            f();
+            env::used_gas().0 - before.0
+        };
+        // Both overwrite a value of the same size, but only `insert` reads the old value into a
+        // register and copies it out.
+        let insert = gas_of(&mut || {
+            map.insert(&1, &vec![1u8]);
+        });
+        let set = gas_of(&mut || map.set(&2, &vec![2u8]));
+        assert!(set < insert, "{} < {}", set, insert);
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), Some(vec![1u8]));
+        assert_eq!(map.get(&2), Some(vec![2u8]));
+        assert_eq!(map.to_vec(), vec![(1, vec![1u8]), (2, vec![2u8])]);
+    }
+
     #[test]
     pub fn test_clear() {
         test_env::setup();
@@ -381,6 +1151,193 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_increment() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(8);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..500 {
+            let key = rng.gen::<u64>() % 20;
+            let by = rng.gen::<u32>() as u64;
+            let expected = *key_to_value.entry(key).and_modify(|v| *v += by).or_insert(by);
+            assert_eq!(map.increment(&key, by), expected);
+        }
+        assert_eq!(map.len(), key_to_value.len() as u64);
+        let actual: HashMap<u64, u64> = map.iter().collect();
+        assert_eq!(actual, key_to_value);
+    }
+
+    #[test]
+    pub fn test_page() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(9);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..15 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>();
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+
+        let first = map.page(0, 10);
+        assert_eq!(first.items.len(), 10);
+        assert_eq!(first.next_cursor, Some(10));
+        let second = map.page(first.next_cursor.unwrap(), 10);
+        assert_eq!(second.items.len(), 5);
+        assert_eq!(second.next_cursor, None);
+
+        let actual: HashMap<u64, u64> = first.items.into_iter().chain(second.items).collect();
+        assert_eq!(actual, key_to_value);
+
+        assert_eq!(map.page(10, 5).next_cursor, None);
+        assert!(map.page(20, 5).items.is_empty());
+    }
+
+    #[test]
+    pub fn test_paged() {
+        test_env::setup_free();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(10);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..1000 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>();
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+
+        let mut actual = HashMap::new();
+        let mut from_index = 0;
+        loop {
+            let page = map.to_vec_paged(from_index, 128);
+            if page.is_empty() {
+                break;
+            }
+            assert!(page.len() <= 128);
+            from_index += page.len() as u64;
+            actual.extend(page);
+        }
+        assert_eq!(from_index, 1000);
+        assert_eq!(actual, key_to_value);
+
+        let tail: Vec<(u64, u64)> = map.iter_from_index(995).collect();
+        assert_eq!(tail, map.iter().skip(995).collect::<Vec<_>>());
+        assert_eq!(map.iter_from_index(1000).len(), 0);
+        assert!(map.to_vec_paged(1000, 10).is_empty());
+        assert!(map.to_vec_paged(u64::MAX, 10).is_empty());
+    }
+
+    #[test]
+    pub fn test_sorted() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(10);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..100 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>() % 10;
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+        let mut expected_keys: Vec<u64> = key_to_value.keys().copied().collect();
+        expected_keys.sort_unstable();
+        let mut expected_values: Vec<u64> = key_to_value.values().copied().collect();
+        expected_values.sort_unstable();
+        assert_eq!(map.keys_sorted(), expected_keys);
+        assert_eq!(map.values_sorted(), expected_values);
+    }
+
+    #[test]
+    pub fn test_iter_sorted_by_key() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(11);
+        let mut keys: Vec<u64> = (0..50).collect();
+        keys.shuffle(&mut rng);
+        for key in keys.iter() {
+            map.insert(key, &(key * 10));
+        }
+        assert_ne!(map.keys().collect::<Vec<_>>(), (0..50).collect::<Vec<_>>());
+
+        let expected: Vec<(u64, u64)> = (0..50).map(|key| (key, key * 10)).collect();
+        assert_eq!(map.iter_sorted_by_key(), expected);
+    }
+
+    #[test]
+    pub fn test_drain() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(6);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..100 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>();
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+        let actual: HashMap<u64, u64> = map.drain().collect();
+        assert_eq!(actual, key_to_value);
+        assert!(map.is_empty());
+        assert!(crate::mock::with_mocked_blockchain(|b| b.take_storage()).is_empty());
+    }
+
+    #[test]
+    pub fn test_drain_early_drop() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(7);
+        let mut key_to_value = HashMap::new();
+        for _ in 0..100 {
+            let key = rng.gen::<u64>();
+            let value = rng.gen::<u64>();
+            key_to_value.insert(key, value);
+            map.insert(&key, &value);
+        }
+        let drained: Vec<(u64, u64)> = map.drain().take(30).collect();
+        assert_eq!(map.len(), 70);
+        for (key, value) in drained {
+            assert_eq!(key_to_value.remove(&key), Some(value));
+            assert_eq!(map.get(&key), None);
+        }
+        let actual: HashMap<u64, u64> = map.iter().collect();
+        assert_eq!(actual, key_to_value);
+    }
+
+    #[test]
+    pub fn test_collect_and_iterate_by_reference() {
+        test_env::setup();
+        let pairs: Vec<(u64, u64)> = (0..20).map(|i| (i, i * i)).collect();
+        let map: UnorderedMap<u64, u64> = pairs.clone().into_iter().collect();
+        assert_eq!(map.len(), 20);
+        assert_eq!(map.get(&7), Some(49));
+
+        let mut iterated = vec![];
+        for (key, value) in &map {
+            iterated.push((key, value));
+        }
+        assert_eq!(iterated, pairs);
+
+        // Maps collected in the same execution don't share the storage.
+        let other: UnorderedMap<u64, u64> = vec![(7, 0)].into_iter().collect();
+        assert_eq!(other.to_vec(), vec![(7, 0)]);
+        assert_eq!(map.get(&7), Some(49));
+    }
+
+    #[test]
+    #[should_panic(expected = "ProhibitedInView { method_name: \"storage_write\" }")]
+    pub fn test_collect_in_view() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        testing_env!(VMContextBuilder::new().is_view(true).build());
+        // Reading works in a view call, but taking the prefix writes its counter.
+        let map: UnorderedMap<u64, u64> = UnorderedMap::new(b"m");
+        assert!(map.is_empty());
+        let _collected: UnorderedMap<u64, u64> = std::iter::empty().collect();
+    }
+
     #[test]
     pub fn test_keys_values() {
         test_env::setup();
@@ -420,6 +1377,77 @@ mod tests {
         assert_eq!(actual, key_to_value);
     }
 
+    #[test]
+    pub fn test_from_hash_map() {
+        test_env::setup();
+        let initial: HashMap<String, u64> = serde_json::from_str(r#"{"a":1,"b":2}"#).unwrap();
+        let map = UnorderedMap::from_hash_map(b"m", initial);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&"a".to_string()), Some(1));
+        assert_eq!(map.get(&"b".to_string()), Some(2));
+        assert_eq!(map.get(&"c".to_string()), None);
+
+        let mut entries = map.to_vec();
+        entries.sort();
+        assert_eq!(entries, vec![("a".to_string(), 1), ("b".to_string(), 2)]);
+    }
+
+    #[test]
+    pub fn test_get_or_insert_with() {
+        test_env::setup();
+        let mut map: UnorderedMap<u64, String> = UnorderedMap::new(b"m");
+        map.insert(&1, &"one".to_string());
+
+        let value = map.get_or_insert_with(&1, || panic!("The key is in the map"));
+        assert_eq!(value, "one");
+        assert_eq!(map.len(), 1);
+
+        let mut calls = 0;
+        let value = map.get_or_insert_with(&2, || {
+            calls += 1;
+            "two".to_string()
+        });
+        assert_eq!(value, "two");
+        assert_eq!(calls, 1);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&2), Some("two".to_string()));
+        assert_eq!(map.to_vec(), vec![(1, "one".to_string()), (2, "two".to_string())]);
+
+        let value = map.get_or_insert_with(&2, || "other".to_string());
+        assert_eq!(value, "two");
+    }
+
+    #[test]
+    pub fn test_from_pairs() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        for key in 0..10u64 {
+            map.insert(&key, &(key * 2));
+        }
+        map.remove(&3);
+        let restored = UnorderedMap::from_pairs(b"r", map.to_vec());
+        assert_eq!(restored.len(), map.len());
+        assert_eq!(restored.to_vec(), map.to_vec());
+        assert_eq!(restored.get(&4), Some(8));
+        assert_eq!(restored.get(&3), None);
+    }
+
+    #[test]
+    pub fn test_iter_rev() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        for key in 0..5u64 {
+            map.insert(&key, &(key * 10));
+        }
+        let entries: Vec<(u64, u64)> = map.iter_rev().collect();
+        assert_eq!(entries, vec![(4, 40), (3, 30), (2, 20), (1, 10), (0, 0)]);
+
+        // The last entry takes the place of the removed one.
+        map.remove(&1);
+        let keys: Vec<u64> = map.iter_rev().map(|(key, _)| key).collect();
+        assert_eq!(keys, vec![3, 2, 4, 0]);
+    }
+
     #[test]
     pub fn test_extend() {
         test_env::setup();
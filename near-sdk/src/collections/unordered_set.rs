@@ -1,6 +1,6 @@
 //! A set implemented on a trie. Unlike `std::collections::HashSet` the elements in this set are not
 //! hashed but are instead serialized.
-use crate::collections::{append, append_slice, Vector};
+use crate::collections::{append, append_slice, register_prefix, Vector};
 use crate::{env, IntoStorageKey};
 use borsh::{BorshDeserialize, BorshSerialize};
 use std::mem::size_of;
@@ -32,6 +32,7 @@ impl<T> UnorderedSet<T> {
         S: IntoStorageKey,
     {
         let prefix = prefix.into_storage_key();
+        register_prefix::<Self>(&prefix);
         let element_index_prefix = append(&prefix, b'i');
         let elements_prefix = append(&prefix, b'e');
 
@@ -127,6 +128,27 @@ where
         self.contains_raw(&Self::serialize_element(element))
     }
 
+    /// Returns for each element of `sorted` whether the set contains it, in the same order.
+    ///
+    /// Each element is serialized exactly once, and since equal elements are adjacent in sorted
+    /// input, runs of duplicates reuse the previous lookup instead of reading the trie again. This
+    /// makes batch membership checks cheaper than calling `contains` in a loop. Unsorted input
+    /// still produces correct results, only without skipping the redundant lookups.
+    pub fn contains_many_sorted(&self, sorted: &[T]) -> Vec<bool> {
+        let mut result = Vec::with_capacity(sorted.len());
+        let mut last: Option<(Vec<u8>, bool)> = None;
+        for element in sorted {
+            let element_raw = Self::serialize_element(element);
+            let contains = match &last {
+                Some((last_raw, last_contains)) if *last_raw == element_raw => *last_contains,
+                _ => self.contains_raw(&element_raw),
+            };
+            result.push(contains);
+            last = Some((element_raw, contains));
+        }
+        result
+    }
+
     /// Removes a value from the set. Returns whether the value was present in the set.
     pub fn remove(&mut self, element: &T) -> bool {
         self.remove_raw(&Self::serialize_element(element))
@@ -154,7 +176,7 @@ where
     }
 
     /// Iterate over deserialized elements.
-    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = T> + ExactSizeIterator + '_ {
         self.elements.iter()
     }
 
@@ -164,6 +186,36 @@ where
         }
     }
 
+    /// Iterate over the elements that are in `self` or in `other`, but not in both: first the
+    /// elements of `self` that are not in `other`, then the elements of `other` that are not in
+    /// `self`.
+    ///
+    /// Every element of both sets is read from the storage and checked with one `contains`
+    /// lookup in the other set, so the cost is proportional to `self.len() + other.len()`.
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a UnorderedSet<T>,
+    ) -> impl Iterator<Item = T> + 'a {
+        self.iter()
+            .filter(move |element| !other.contains(element))
+            .chain(other.iter().filter(move |element| !self.contains(element)))
+    }
+
+    /// Returns `true` if every element of `self` is also in `other`.
+    ///
+    /// Costs one `contains` lookup in `other` per element of `self`. Returns `false` without any
+    /// lookups if `self` is larger than `other`, and stops at the first element that `other`
+    /// does not contain.
+    pub fn is_subset(&self, other: &UnorderedSet<T>) -> bool {
+        self.len() <= other.len() && self.iter().all(|element| other.contains(&element))
+    }
+
+    /// Returns `true` if every element of `other` is also in `self`. The cost is the same as of
+    /// `other.is_subset(self)`.
+    pub fn is_superset(&self, other: &UnorderedSet<T>) -> bool {
+        other.is_subset(self)
+    }
+
     /// Returns a view of elements as a vector.
     /// It's sometimes useful to have random access to the elements.
     pub fn as_vector(&self) -> &Vector<T> {
@@ -263,6 +315,24 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_contains_many_sorted() {
+        test_env::setup();
+        let mut set = UnorderedSet::new(b"s");
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(6);
+        let mut set_tmp = HashSet::new();
+        for _ in 0..200 {
+            let key = rng.gen::<u64>() % 500;
+            set_tmp.insert(key);
+            set.insert(&key);
+        }
+        let mut queries: Vec<u64> = (0..300).map(|_| rng.gen::<u64>() % 500).collect();
+        queries.sort_unstable();
+        let expected: Vec<bool> = queries.iter().map(|key| set_tmp.contains(key)).collect();
+        assert_eq!(set.contains_many_sorted(&queries), expected);
+        assert!(set.contains_many_sorted(&[]).is_empty());
+    }
+
     #[test]
     pub fn test_to_vec() {
         test_env::setup();
@@ -333,4 +403,65 @@ mod tests {
         let actual: HashSet<u64> = set.iter().collect();
         assert_eq!(actual, keys);
     }
+
+    #[test]
+    pub fn test_against_hash_set() {
+        test_env::setup();
+        let mut set = UnorderedSet::new(b"s");
+        let mut baseline = HashSet::new();
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(6);
+        for _ in 0..500 {
+            // Small key space, so that the operations often hit existing elements.
+            let key = rng.gen::<u64>() % 50;
+            match rng.gen::<u8>() % 3 {
+                0 => assert_eq!(set.insert(&key), baseline.insert(key)),
+                1 => assert_eq!(set.remove(&key), baseline.remove(&key)),
+                _ => assert_eq!(set.contains(&key), baseline.contains(&key)),
+            }
+            assert_eq!(set.len(), baseline.len() as u64);
+        }
+        assert_eq!(HashSet::from_iter(set.iter()), baseline);
+        assert_eq!(HashSet::from_iter(set.to_vec()), baseline);
+    }
+
+    #[test]
+    pub fn test_set_algebra() {
+        test_env::setup_free();
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(7);
+        for round in 0..20u8 {
+            let mut a = UnorderedSet::new(vec![b'a', round]);
+            let mut b = UnorderedSet::new(vec![b'b', round]);
+            let mut baseline_a = HashSet::new();
+            let mut baseline_b = HashSet::new();
+            for _ in 0..rng.gen::<u8>() % 20 {
+                let key = rng.gen::<u64>() % 30;
+                a.insert(&key);
+                baseline_a.insert(key);
+            }
+            // Every few rounds `b` is built as a superset of `a`, so both outcomes are tested.
+            if round % 3 == 0 {
+                b.extend(baseline_a.iter().copied());
+                baseline_b.extend(baseline_a.iter().copied());
+            }
+            for _ in 0..rng.gen::<u8>() % 20 {
+                let key = rng.gen::<u64>() % 30;
+                b.insert(&key);
+                baseline_b.insert(key);
+            }
+
+            let actual: Vec<u64> = a.symmetric_difference(&b).collect();
+            let expected: HashSet<u64> =
+                baseline_a.symmetric_difference(&baseline_b).copied().collect();
+            assert_eq!(actual.len(), expected.len());
+            assert_eq!(HashSet::from_iter(actual), expected);
+
+            assert_eq!(a.is_subset(&b), baseline_a.is_subset(&baseline_b));
+            assert_eq!(b.is_subset(&a), baseline_b.is_subset(&baseline_a));
+            assert_eq!(a.is_superset(&b), baseline_a.is_superset(&baseline_b));
+            assert_eq!(b.is_superset(&a), baseline_b.is_superset(&baseline_a));
+            assert!(a.is_subset(&a));
+            assert!(a.is_superset(&a));
+            assert_eq!(a.symmetric_difference(&a).count(), 0);
+        }
+    }
 }
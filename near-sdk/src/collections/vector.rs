@@ -1,10 +1,12 @@
 //! A vector implemented on a trie. Unlike standard vector does not support insertion and removal
 //! of an element results in the last element being placed in the empty position.
 use std::marker::PhantomData;
+use std::mem::size_of;
+use std::ops::{Add, Range};
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 
-use crate::collections::append_slice;
+use crate::collections::{append, append_slice, register_prefix};
 use crate::{env, IntoStorageKey};
 
 const ERR_INCONSISTENT_STATE: &[u8] = b"The collection is an inconsistent state. Did previous smart contract execution terminate unexpectedly?";
@@ -16,15 +18,74 @@ fn expect_consistent_state<T>(val: Option<T>) -> T {
     val.unwrap_or_else(|| env::panic(ERR_INCONSISTENT_STATE))
 }
 
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Set in the serialized length of a vector that keeps a checksum, see
+/// [`Vector::new_with_checksum`]. Lengths never get close to `2^63`, so the bit is free, and
+/// vectors without a checksum keep the serialized form they had before checksums were added.
+const CHECKSUM_FLAG: u64 = 1 << 63;
+
+/// FNV-1a hash of an element's raw bytes together with the index it is stored at.
+fn element_hash(index: u64, raw_element: &[u8]) -> u64 {
+    index
+        .to_le_bytes()
+        .iter()
+        .chain(raw_element)
+        .fold(FNV_OFFSET_BASIS, |hash, byte| (hash ^ u64::from(*byte)).wrapping_mul(FNV_PRIME))
+}
+
+/// The error returned by [`Vector::try_get`] and [`Vector::try_replace`] when the index is out of
+/// bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IndexError {
+    /// The index that was accessed.
+    pub index: u64,
+    /// The length of the vector at the time of the access.
+    pub len: u64,
+}
+
+impl std::fmt::Display for IndexError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "index {} is out of bounds of the vector of length {}", self.index, self.len)
+    }
+}
+
+impl std::error::Error for IndexError {}
+
 /// An iterable implementation of vector that stores its content on the trie.
 /// Uses the following map: index -> element.
-#[derive(BorshSerialize, BorshDeserialize)]
+///
+/// A vector created with [`new_with_checksum`](Self::new_with_checksum) also keeps a running
+/// [`checksum`](Self::checksum) of its content under the key `prefix + b"c"`.
 #[cfg_attr(not(feature = "expensive-debug"), derive(Debug))]
 pub struct Vector<T> {
     len: u64,
     prefix: Vec<u8>,
-    #[borsh_skip]
     el: PhantomData<T>,
+    /// Whether the checksum is kept, stored as [`CHECKSUM_FLAG`] in the serialized length.
+    has_checksum: bool,
+}
+
+impl<T> BorshSerialize for Vector<T> {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        let len = if self.has_checksum { self.len | CHECKSUM_FLAG } else { self.len };
+        len.serialize(writer)?;
+        self.prefix.serialize(writer)
+    }
+}
+
+impl<T> BorshDeserialize for Vector<T> {
+    fn deserialize(buf: &mut &[u8]) -> std::io::Result<Self> {
+        let len = u64::deserialize(buf)?;
+        let prefix = Vec::<u8>::deserialize(buf)?;
+        Ok(Self {
+            len: len & !CHECKSUM_FLAG,
+            prefix,
+            el: PhantomData,
+            has_checksum: len & CHECKSUM_FLAG != 0,
+        })
+    }
 }
 
 impl<T> Vector<T> {
@@ -43,13 +104,123 @@ impl<T> Vector<T> {
     where
         S: IntoStorageKey,
     {
-        Self { len: 0, prefix: prefix.into_storage_key(), el: PhantomData }
+        let prefix = prefix.into_storage_key();
+        register_prefix::<Self>(&prefix);
+        Self { len: 0, prefix, el: PhantomData, has_checksum: false }
+    }
+
+    /// Same as [`new`](Self::new), but the vector keeps a [`checksum`](Self::checksum) of its
+    /// content. The checksum is stored under the key `prefix + b"c"`, so it costs one extra key, and
+    /// every mutation reads and writes that key in addition to the element.
+    pub fn new_with_checksum<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+    {
+        Self { has_checksum: true, ..Self::new(prefix) }
+    }
+
+    /// Same as [`new`](Self::new), but also checks that the prefix was not used for a vector of a
+    /// different element type, which would silently misread the stored elements. The name of the
+    /// element type from its [`BorshSchema`] is written under the key `prefix + b"t"` the first
+    /// time, and later constructions panic if the stored name is different.
+    ///
+    /// This costs one extra key, which is not removed by [`clear`](Self::clear). Vectors that are
+    /// deserialized as part of the contract state are not checked again.
+    pub fn new_checked<S>(prefix: S) -> Self
+    where
+        S: IntoStorageKey,
+        T: BorshSchema,
+    {
+        let vector = Self::new(prefix);
+        let type_tag_key = append(&vector.prefix, b't');
+        let type_name = T::declaration();
+        match env::storage_read(&type_tag_key) {
+            Some(stored) if stored != type_name.as_bytes() => env::panic(
+                format!(
+                    "The vector was created for elements of type {}, not {}",
+                    String::from_utf8_lossy(&stored),
+                    type_name
+                )
+                .as_bytes(),
+            ),
+            Some(_) => {}
+            None => {
+                env::storage_write(&type_tag_key, type_name.as_bytes());
+            }
+        }
+        vector
     }
 
     fn index_to_lookup_key(&self, index: u64) -> Vec<u8> {
         append_slice(&self.prefix, &index.to_le_bytes()[..])
     }
 
+    /// Returns a checksum of the content of the vector, which can be compared with a previously
+    /// cached value to cheaply detect whether the vector has been changed since.
+    ///
+    /// The checksum is the XOR of FNV-1a hashes of every element together with its index. A vector
+    /// created with [`new_with_checksum`](Self::new_with_checksum) updates it on every mutation, so
+    /// reading it costs a single storage read. For other vectors it is computed by reading every
+    /// element. It only depends on the current content: mutations that leave the content as it
+    /// was, like replacing an element with an equal one, do not change it. This is a change hint,
+    /// not a cryptographic hash, and can collide for different contents.
+    pub fn checksum(&self) -> u64 {
+        if self.has_checksum {
+            self.stored_checksum()
+        } else {
+            self.compute_checksum()
+        }
+    }
+
+    /// Starts keeping a [`checksum`](Self::checksum) for a vector that was created without one,
+    /// for example with [`new`](Self::new) before checksums existed. This reads every element once
+    /// to compute the checksum of the current content. Does nothing if the vector already keeps
+    /// a checksum.
+    ///
+    /// The flag is stored with the vector, so the contract state that contains the vector has to
+    /// be written back for the checksum to be kept in later calls.
+    pub fn enable_checksum(&mut self) {
+        if self.has_checksum {
+            return;
+        }
+        let checksum = self.compute_checksum();
+        self.has_checksum = true;
+        self.update_checksum(checksum);
+    }
+
+    fn checksum_key(&self) -> Vec<u8> {
+        append(&self.prefix, b'c')
+    }
+
+    fn compute_checksum(&self) -> u64 {
+        self.iter_indexed_raw()
+            .fold(0, |checksum, (index, raw_element)| checksum ^ element_hash(index, &raw_element))
+    }
+
+    fn stored_checksum(&self) -> u64 {
+        env::storage_read(&self.checksum_key())
+            .map(|raw_checksum| {
+                let mut checksum = [0u8; size_of::<u64>()];
+                checksum.copy_from_slice(&raw_checksum);
+                u64::from_le_bytes(checksum)
+            })
+            .unwrap_or(0)
+    }
+
+    /// Applies `delta` to the stored checksum. The key is removed when the checksum is zero, e.g.
+    /// when the vector is empty.
+    fn update_checksum(&mut self, delta: u64) {
+        if !self.has_checksum || delta == 0 {
+            return;
+        }
+        let checksum = self.stored_checksum() ^ delta;
+        if checksum == 0 {
+            env::storage_remove(&self.checksum_key());
+        } else {
+            env::storage_write(&self.checksum_key(), &checksum.to_le_bytes());
+        }
+    }
+
     /// Returns the serialized element by index or `None` if it is not present.
     pub fn get_raw(&self, index: u64) -> Option<Vec<u8>> {
         if index >= self.len {
@@ -74,17 +245,22 @@ impl<T> Vector<T> {
         } else {
             let lookup_key = self.index_to_lookup_key(index);
             let raw_last_value = self.pop_raw().expect("checked `index < len` above, so `len > 0`");
-            if env::storage_write(&lookup_key, &raw_last_value) {
+            let raw_evicted = if env::storage_write(&lookup_key, &raw_last_value) {
                 expect_consistent_state(env::storage_get_evicted())
             } else {
                 env::panic(ERR_INCONSISTENT_STATE)
-            }
+            };
+            self.update_checksum(
+                element_hash(index, &raw_evicted) ^ element_hash(index, &raw_last_value),
+            );
+            raw_evicted
         }
     }
 
     /// Appends a serialized element to the back of the collection.
     pub fn push_raw(&mut self, raw_element: &[u8]) {
         let lookup_key = self.index_to_lookup_key(self.len);
+        self.update_checksum(element_hash(self.len, raw_element));
         self.len += 1;
         env::storage_write(&lookup_key, raw_element);
     }
@@ -103,10 +279,30 @@ impl<T> Vector<T> {
             } else {
                 env::panic(ERR_INCONSISTENT_STATE)
             };
+            self.update_checksum(element_hash(last_index, &raw_last_value));
             Some(raw_last_value)
         }
     }
 
+    /// Removes up to `k` elements from the front of the vector and returns them in order without
+    /// deserializing. Returns fewer than `k` elements if the vector is shorter.
+    ///
+    /// The remaining elements are moved to the front to keep the order, so this rewrites every
+    /// element after the first `k`, which costs `O(len)` storage writes.
+    pub fn take_front_raw(&mut self, k: u64) -> Vec<Vec<u8>> {
+        let k = k.min(self.len);
+        let taken: Vec<Vec<u8>> =
+            (0..k).map(|i| expect_consistent_state(self.get_raw(i))).collect();
+        for i in k..self.len {
+            let raw_element = expect_consistent_state(self.get_raw(i));
+            self.set_raw(i - k, &raw_element);
+        }
+        for _ in 0..k {
+            self.pop_raw();
+        }
+        taken
+    }
+
     /// Inserts a serialized element at `index`, returns a serialized evicted element.
     ///
     /// # Panics
@@ -117,11 +313,45 @@ impl<T> Vector<T> {
             env::panic(ERR_INDEX_OUT_OF_BOUNDS)
         } else {
             let lookup_key = self.index_to_lookup_key(index);
-            if env::storage_write(&lookup_key, &raw_element) {
+            let raw_evicted = if env::storage_write(&lookup_key, &raw_element) {
                 expect_consistent_state(env::storage_get_evicted())
             } else {
                 env::panic(ERR_INCONSISTENT_STATE);
-            }
+            };
+            self.update_checksum(
+                element_hash(index, &raw_evicted) ^ element_hash(index, raw_element),
+            );
+            raw_evicted
+        }
+    }
+
+    /// Writes a serialized element at `index` like [`replace_raw`](Self::replace_raw), but doesn't
+    /// read the evicted element, unless the vector keeps a [`checksum`](Self::checksum), which
+    /// needs the evicted element to be updated.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn set_raw(&mut self, index: u64, raw_element: &[u8]) {
+        if index >= self.len {
+            env::panic(ERR_INDEX_OUT_OF_BOUNDS)
+        }
+        if self.has_checksum {
+            self.replace_raw(index, raw_element);
+        } else if !env::storage_write(&self.index_to_lookup_key(index), raw_element) {
+            env::panic(ERR_INCONSISTENT_STATE)
+        }
+    }
+
+    /// Rewrites every element in place with the serialized form returned by `f` for its current
+    /// serialized form. The length, the order of the elements, and the prefix stay the same, so a
+    /// migration can change the element type and deserialize the vector with the new type.
+    ///
+    /// Costs one storage read and one storage write per element.
+    pub fn replace_all<F: FnMut(Vec<u8>) -> Vec<u8>>(&mut self, mut f: F) {
+        for index in 0..self.len {
+            let raw_element = expect_consistent_state(self.get_raw(index));
+            self.set_raw(index, &f(raw_element));
         }
     }
 
@@ -133,7 +363,20 @@ impl<T> Vector<T> {
         })
     }
 
+    /// Iterate over raw serialized elements together with their indices. Useful for migrations
+    /// that rewrite elements in place with `replace_raw`.
+    pub fn iter_indexed_raw(&self) -> impl Iterator<Item = (u64, Vec<u8>)> + '_ {
+        (0..self.len).map(move |i| {
+            let lookup_key = self.index_to_lookup_key(i);
+            (i, expect_consistent_state(env::storage_read(&lookup_key)))
+        })
+    }
+
     /// Extends vector from the given collection of serialized elements.
+    ///
+    /// The length is kept in memory and only stored together with the vector, so this costs a
+    /// single storage write per element. A vector that keeps a [`checksum`](Self::checksum) also
+    /// reads and writes the checksum key once per element.
     pub fn extend_raw<IT: IntoIterator<Item = Vec<u8>>>(&mut self, iter: IT) {
         for el in iter {
             self.push_raw(&el)
@@ -148,6 +391,9 @@ impl<T> Vector<T> {
             let lookup_key = self.index_to_lookup_key(i);
             env::storage_remove(&lookup_key);
         }
+        if self.has_checksum {
+            env::storage_remove(&self.checksum_key());
+        }
         self.len = 0;
     }
 }
@@ -167,6 +413,9 @@ where
     }
 
     /// Extends vector from the given collection.
+    ///
+    /// The elements are serialized and written one by one, so if serializing an element panics,
+    /// the vector holds exactly the elements before it.
     pub fn extend<IT: IntoIterator<Item = T>>(&mut self, iter: IT) {
         for el in iter {
             self.push(&el)
@@ -187,6 +436,12 @@ where
         self.get_raw(index).map(|x| Self::deserialize_element(&x))
     }
 
+    /// Returns the element by index like [`get`](Self::get), but an out of bounds index is
+    /// reported as an [`IndexError`] with the index and the length of the vector.
+    pub fn try_get(&self, index: u64) -> Result<T, IndexError> {
+        self.get(index).ok_or(IndexError { index, len: self.len })
+    }
+
     /// Removes an element from the vector and returns it.
     /// The removed element is replaced by the last element of the vector.
     /// Does not preserve ordering, but is `O(1)`.
@@ -199,21 +454,198 @@ where
         Self::deserialize_element(&raw_evicted)
     }
 
+    /// Removes up to `k` elements from the front of the vector and returns them in order. See
+    /// [`take_front_raw`] for the cost.
+    ///
+    /// [`take_front_raw`]: Vector::take_front_raw
+    pub fn take_front(&mut self, k: u64) -> Vec<T> {
+        self.take_front_raw(k)
+            .iter()
+            .map(|raw_element| Self::deserialize_element(raw_element))
+            .collect()
+    }
+
     /// Removes the last element from a vector and returns it, or `None` if it is empty.
     pub fn pop(&mut self) -> Option<T> {
         self.pop_raw().map(|x| Self::deserialize_element(&x))
     }
 
-    /// Iterate over deserialized elements.
-    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
-        self.iter_raw().map(|raw_element| Self::deserialize_element(&raw_element))
+    /// Iterate over deserialized elements. The iterator can be reversed, and `nth`, `last` and
+    /// `count` compute the index instead of reading the skipped elements.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = T> + ExactSizeIterator + '_ {
+        Iter { vec: self, range: 0..self.len }
+    }
+
+    /// Iterate over deserialized elements from the last to the first, reading them by descending
+    /// index. Shorthand for `iter().rev()`.
+    pub fn iter_rev(&self) -> impl Iterator<Item = T> + ExactSizeIterator + '_ {
+        self.iter().rev()
+    }
+
+    /// Iterate over deserialized elements starting from the element with index `from_index`. The
+    /// skipped elements are not read from the storage. If `from_index` is out of range, the
+    /// iterator is empty.
+    pub fn iter_from_index(
+        &self,
+        from_index: u64,
+    ) -> impl DoubleEndedIterator<Item = T> + ExactSizeIterator + '_ {
+        Iter { vec: self, range: std::cmp::min(from_index, self.len)..self.len }
     }
 
     pub fn to_vec(&self) -> Vec<T> {
         self.iter().collect()
     }
+
+    /// Copies at most `limit` elements starting from the element with index `from_index` into a
+    /// `Vec`, which is useful to return a large vector from a view method in pages. Returns an
+    /// empty `Vec` if `from_index` is out of range.
+    pub fn to_vec_paged(&self, from_index: u64, limit: u64) -> Vec<T> {
+        self.iter_from_index(from_index).take(limit as usize).collect()
+    }
+
+    /// Folds every element into an accumulator, like [`Iterator::fold`]. Unlike `iter().fold(..)`,
+    /// the elements are read from the storage into a single reused buffer, so no memory is
+    /// allocated per element, which makes it a better fit for aggregating large vectors, e.g. in
+    /// view methods.
+    pub fn fold<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, T) -> B,
+    {
+        let mut lookup_key = self.index_to_lookup_key(0);
+        let index_start = lookup_key.len() - size_of::<u64>();
+        let mut raw_element = Vec::new();
+        let mut acc = init;
+        for index in 0..self.len {
+            lookup_key[index_start..].copy_from_slice(&index.to_le_bytes());
+            if !env::storage_read_into(&lookup_key, &mut raw_element) {
+                env::panic(ERR_INCONSISTENT_STATE);
+            }
+            acc = f(acc, Self::deserialize_element(&raw_element));
+        }
+        acc
+    }
+
+    /// Returns the sum of all elements, or `T::default()` if the vector is empty. See [`fold`] for
+    /// how the elements are read.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::collections::Vector;
+    ///
+    /// let mut vec = Vector::new(b"v");
+    /// vec.extend(vec![1u128, 2, 3]);
+    /// assert_eq!(vec.sum(), 6);
+    /// ```
+    ///
+    /// [`fold`]: Vector::fold
+    pub fn sum(&self) -> T
+    where
+        T: Add<Output = T> + Default,
+    {
+        self.fold(T::default(), |acc, element| acc + element)
+    }
+
+    /// Returns an iterator over all contiguous windows of length `size`. The windows overlap, like
+    /// with [`slice::windows`]. If the vector is shorter than `size` or `size` is `0`, the iterator
+    /// returns no values.
+    ///
+    /// Every window reads all of its `size` elements from the storage again, so iterating over all
+    /// windows reads `(len - size + 1) * size` elements in total.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::collections::Vector;
+    ///
+    /// let mut vec = Vector::new(b"v");
+    /// vec.extend(vec![1u32, 2, 3]);
+    /// let windows: Vec<Vec<u32>> = vec.windows(2).collect();
+    /// assert_eq!(windows, vec![vec![1, 2], vec![2, 3]]);
+    /// ```
+    pub fn windows(&self, size: u64) -> impl Iterator<Item = Vec<T>> + '_ {
+        let count = if size == 0 { 0 } else { (self.len() + 1).saturating_sub(size) };
+        (0..count).map(move |start| {
+            (start..start + size)
+                .map(|index| match self.get_raw(index) {
+                    Some(raw_element) => Self::deserialize_element(&raw_element),
+                    None => env::panic(ERR_INCONSISTENT_STATE),
+                })
+                .collect()
+        })
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Vector<T>
+where
+    T: BorshDeserialize,
+{
+    type Item = T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter { vec: self, range: 0..self.len }
+    }
+}
+
+/// Iterator over the elements of a [`Vector`] within the range of indices.
+pub struct Iter<'a, T> {
+    vec: &'a Vector<T>,
+    range: Range<u64>,
+}
+
+impl<'a, T> Iter<'a, T>
+where
+    T: BorshDeserialize,
+{
+    fn element(&self, index: u64) -> T {
+        Vector::<T>::deserialize_element(&expect_consistent_state(self.vec.get_raw(index)))
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T>
+where
+    T: BorshDeserialize,
+{
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.range.next().map(|index| self.element(index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = (self.range.end - self.range.start) as usize;
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth(n).map(|index| self.element(index))
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+}
+
+impl<'a, T> DoubleEndedIterator for Iter<'a, T>
+where
+    T: BorshDeserialize,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.range.next_back().map(|index| self.element(index))
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        self.range.nth_back(n).map(|index| self.element(index))
+    }
 }
 
+impl<'a, T> ExactSizeIterator for Iter<'a, T> where T: BorshDeserialize {}
+
+impl<'a, T> std::iter::FusedIterator for Iter<'a, T> where T: BorshDeserialize {}
+
 impl<T> Vector<T>
 where
     T: BorshSerialize + BorshDeserialize,
@@ -227,6 +659,15 @@ where
         let raw_element = Self::serialize_element(element);
         Self::deserialize_element(&self.replace_raw(index, &raw_element))
     }
+
+    /// Replaces the element like [`replace`](Self::replace), but instead of panicking on an out of
+    /// bounds index returns an [`IndexError`] and leaves the vector unchanged.
+    pub fn try_replace(&mut self, index: u64, element: &T) -> Result<T, IndexError> {
+        if index >= self.len {
+            return Err(IndexError { index, len: self.len });
+        }
+        Ok(self.replace(index, element))
+    }
 }
 
 #[cfg(feature = "expensive-debug")]
@@ -239,10 +680,11 @@ impl<T: std::fmt::Debug + BorshDeserialize> std::fmt::Debug for Vector<T> {
 #[cfg(not(target_arch = "wasm32"))]
 #[cfg(test)]
 mod tests {
-    use borsh::BorshDeserialize;
+    use borsh::{BorshDeserialize, BorshSerialize};
     use rand::{Rng, SeedableRng};
 
-    use crate::collections::Vector;
+    use crate::collections::{IndexError, Vector};
+    use crate::env;
     use crate::test_utils::test_env;
 
     #[test]
@@ -263,6 +705,212 @@ mod tests {
         }
     }
 
+    #[test]
+    pub fn test_windows() {
+        test_env::setup();
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(5);
+        let mut vec = Vector::new(b"v".to_vec());
+        let mut baseline = vec![];
+        for _ in 0..20 {
+            let value = rng.gen::<u64>();
+            vec.push(&value);
+            baseline.push(value);
+        }
+        for size in 1..=21 {
+            let actual: Vec<Vec<u64>> = vec.windows(size).collect();
+            let expected: Vec<Vec<u64>> =
+                baseline.windows(size as usize).map(|w| w.to_vec()).collect();
+            assert_eq!(actual, expected);
+        }
+        assert_eq!(vec.windows(0).count(), 0);
+    }
+
+    #[test]
+    pub fn test_iter_double_ended() {
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        assert_eq!(vec.iter().len(), 0);
+        assert_eq!(vec.iter().next_back(), None);
+        vec.extend(0..10u64);
+
+        let mut iter = vec.iter();
+        assert_eq!(iter.len(), 10);
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(9));
+        assert_eq!(iter.len(), 8);
+        assert_eq!(iter.nth(2), Some(3));
+        assert_eq!(iter.nth_back(1), Some(7));
+        assert_eq!(iter.len(), 3);
+        assert_eq!(iter.collect::<Vec<_>>(), vec![4, 5, 6]);
+
+        assert_eq!(vec.iter().rev().collect::<Vec<_>>(), (0..10).rev().collect::<Vec<_>>());
+        assert_eq!(vec.iter().rev().take(3).collect::<Vec<_>>(), vec![9, 8, 7]);
+        assert_eq!(vec.iter().nth(7), Some(7));
+        assert_eq!(vec.iter().nth(10), None);
+        assert_eq!(vec.iter().last(), Some(9));
+        assert_eq!(vec.iter().count(), 10);
+        assert_eq!(vec.iter().skip(4).len(), 6);
+    }
+
+    #[test]
+    pub fn test_paged() {
+        test_env::setup_free();
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.extend(0..1000u64);
+
+        let mut actual = vec![];
+        let mut from_index = 0;
+        loop {
+            let page = vec.to_vec_paged(from_index, 300);
+            if page.is_empty() {
+                break;
+            }
+            assert!(page.len() <= 300);
+            from_index += page.len() as u64;
+            actual.extend(page);
+        }
+        assert_eq!(actual, (0..1000u64).collect::<Vec<_>>());
+
+        assert_eq!(
+            vec.iter_from_index(990).collect::<Vec<_>>(),
+            (990..1000u64).collect::<Vec<_>>()
+        );
+        assert_eq!(vec.iter_from_index(995).next_back(), Some(999));
+        assert_eq!(vec.iter_from_index(1000).len(), 0);
+        assert!(vec.to_vec_paged(1000, 10).is_empty());
+        assert!(vec.to_vec_paged(u64::MAX, 10).is_empty());
+        assert!(vec.to_vec_paged(0, 0).is_empty());
+    }
+
+    #[test]
+    pub fn test_new_checked() {
+        test_env::setup();
+        let mut vec: Vector<u32> = Vector::new_checked(b"v".to_vec());
+        vec.push(&1);
+        drop(vec);
+        // The same element type can be used again.
+        let vec: Vector<u32> = Vector::new_checked(b"v".to_vec());
+        assert_eq!(vec.len(), 0);
+        // A vector of another type under the same prefix is reported by `debug-prefix-collision`
+        // within one execution, so it is opened in a new one.
+        test_env::setup();
+        // Unchecked construction ignores the type tag.
+        let _vec: Vector<u64> = Vector::new(b"v".to_vec());
+        assert_eq!(env::storage_read(b"vt"), Some(b"u32".to_vec()));
+    }
+
+    #[test]
+    #[should_panic(expected = "The vector was created for elements of type u32, not u64")]
+    pub fn test_new_checked_type_mismatch() {
+        test_env::setup();
+        let mut vec: Vector<u32> = Vector::new_checked(b"v".to_vec());
+        vec.push(&1);
+        drop(vec);
+        test_env::setup();
+        let _vec: Vector<u64> = Vector::new_checked(b"v".to_vec());
+    }
+
+    #[test]
+    pub fn test_take_front() {
+        test_env::setup();
+        let mut queue = Vector::new(b"q".to_vec());
+        queue.extend(0..10u64);
+        let mut batches = vec![];
+        while !queue.is_empty() {
+            batches.push(queue.take_front(4));
+            // The remaining elements keep their order.
+            assert_eq!(queue.to_vec(), (10 - queue.len()..10).collect::<Vec<_>>());
+        }
+        assert_eq!(batches, vec![vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+        assert!(queue.take_front(4).is_empty());
+
+        queue.extend(0..3u64);
+        assert!(queue.take_front(0).is_empty());
+        assert_eq!(queue.take_front(3), vec![0, 1, 2]);
+        // The trie entries of the taken elements are removed.
+        for index in 0..3u64 {
+            assert!(!crate::env::storage_has_key(&[&b"q"[..], &index.to_le_bytes()].concat()));
+        }
+    }
+
+    #[test]
+    pub fn test_sum_fold() {
+        test_env::setup();
+        let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(6);
+        let mut vec = Vector::new(b"v".to_vec());
+        assert_eq!(vec.sum(), 0u128);
+        let mut expected = 0u128;
+        let mut baseline = vec![];
+        for _ in 0..100 {
+            let value = rng.gen::<u64>() as u128;
+            vec.push(&value);
+            baseline.push(value);
+            expected += value;
+        }
+        assert_eq!(vec.sum(), expected);
+        assert_eq!(vec.fold(0u128, |acc, value| acc.max(value)), *baseline.iter().max().unwrap());
+        let collected = vec.fold(vec![], |mut acc, value| {
+            acc.push(value);
+            acc
+        });
+        assert_eq!(collected, baseline);
+    }
+
+    #[test]
+    pub fn test_iter_indexed_raw() {
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        for value in 0..10u64 {
+            vec.push(&(value * 2));
+        }
+        let indices: Vec<u64> = vec.iter_indexed_raw().map(|(index, _)| index).collect();
+        assert_eq!(indices, (0..10).collect::<Vec<_>>());
+        // Migrate every element in place using the yielded index.
+        let migrated: Vec<(u64, Vec<u8>)> = vec
+            .iter_indexed_raw()
+            .map(|(index, raw)| {
+                (index, (u64::try_from_slice(&raw).unwrap() + 1).try_to_vec().unwrap())
+            })
+            .collect();
+        for (index, raw) in migrated {
+            vec.replace_raw(index, &raw);
+        }
+        assert_eq!(vec.to_vec(), (0..10).map(|v| v * 2 + 1).collect::<Vec<u64>>());
+    }
+
+    #[test]
+    pub fn test_iter_rev() {
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.extend(0..10u64);
+        let reversed: Vec<u64> = vec.iter_rev().collect();
+        assert_eq!(reversed, (0..10).rev().collect::<Vec<_>>());
+        assert_eq!(vec.iter_rev().len(), 10);
+        assert_eq!(vec.iter_rev().next(), vec.get(9));
+
+        let empty: Vector<u64> = Vector::new(b"e".to_vec());
+        assert_eq!(empty.iter_rev().next(), None);
+    }
+
+    #[test]
+    pub fn test_replace_all() {
+        test_env::setup();
+        let mut vec: Vector<u32> = Vector::new(b"v".to_vec());
+        vec.extend(0..10u32);
+        // Migrate the elements from `u32` to `u64`.
+        vec.replace_all(|raw| {
+            (u64::from(u32::try_from_slice(&raw).unwrap()) * 3).try_to_vec().unwrap()
+        });
+        assert_eq!(vec.len(), 10);
+
+        let migrated: Vector<u64> = Vector::try_from_slice(&vec.try_to_vec().unwrap()).unwrap();
+        assert_eq!(migrated.to_vec(), (0..10).map(|v| v * 3).collect::<Vec<u64>>());
+
+        let mut empty: Vector<u32> = Vector::new(b"e".to_vec());
+        empty.replace_all(|_| unreachable!());
+        assert!(empty.is_empty());
+    }
+
     #[test]
     pub fn test_replace() {
         test_env::setup();
@@ -355,6 +1003,187 @@ mod tests {
         assert_eq!(actual, baseline);
     }
 
+    #[test]
+    pub fn test_into_iterator() {
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.extend(vec![1u64, 2, 3]);
+        let mut sum = 0;
+        for value in &vec {
+            sum += value;
+        }
+        assert_eq!(sum, 6);
+        assert_eq!((&vec).into_iter().rev().collect::<Vec<_>>(), vec![3, 2, 1]);
+    }
+
+    #[test]
+    pub fn test_try_get_replace() {
+        test_env::setup();
+        let mut vec = Vector::new_with_checksum(b"v".to_vec());
+        vec.extend(vec![1u64, 2, 3]);
+        assert_eq!(vec.try_get(1), Ok(2));
+        assert_eq!(vec.try_replace(1, &20), Ok(2));
+        assert_eq!(vec.try_get(1), Ok(20));
+
+        let checksum = vec.checksum();
+        let err = vec.try_replace(3, &4).unwrap_err();
+        assert_eq!(err, IndexError { index: 3, len: 3 });
+        assert_eq!(err.to_string(), "index 3 is out of bounds of the vector of length 3");
+        assert_eq!(vec.try_get(5), Err(IndexError { index: 5, len: 3 }));
+        assert_eq!(vec.to_vec(), vec![1, 20, 3]);
+        assert_eq!(vec.checksum(), checksum);
+    }
+
+    #[test]
+    pub fn test_extend_bulk() {
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.extend(0..100u64);
+        assert_eq!(vec.len(), 100);
+        for index in [0, 1, 42, 98, 99].iter() {
+            assert_eq!(vec.get(*index), Some(*index));
+        }
+        assert_eq!(vec.get(100), None);
+
+        let mut raw = Vector::<u64>::new(b"r".to_vec());
+        raw.extend_raw((0..100u64).map(|i| i.try_to_vec().unwrap()));
+        assert_eq!(raw.len(), 100);
+        assert_eq!(raw.get(57), Some(57));
+        assert_eq!(raw.to_vec(), vec.to_vec());
+    }
+
+    #[test]
+    pub fn test_extend_serialization_panic() {
+        #[derive(BorshDeserialize, Debug, PartialEq)]
+        struct Element(u64);
+
+        impl BorshSerialize for Element {
+            fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+                if self.0 == 3 {
+                    return Err(std::io::Error::new(std::io::ErrorKind::Other, "unserializable"));
+                }
+                self.0.serialize(writer)
+            }
+        }
+
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            vec.extend((0..10).map(Element));
+        }));
+        assert!(result.is_err());
+        assert_eq!(vec.len(), 3);
+        assert_eq!(vec.to_vec(), vec![Element(0), Element(1), Element(2)]);
+    }
+
+    #[test]
+    pub fn test_checksum() {
+        test_env::setup();
+        let mut vec = Vector::new_with_checksum(b"v".to_vec());
+        assert_eq!(vec.checksum(), 0);
+        vec.extend(vec![1u64, 2, 3]);
+        let checksum = vec.checksum();
+        assert_ne!(checksum, 0);
+
+        // Mutations that leave the content unchanged keep the checksum.
+        vec.replace(1, &2);
+        assert_eq!(vec.checksum(), checksum);
+        vec.push(&4);
+        assert_ne!(vec.checksum(), checksum);
+        vec.pop();
+        assert_eq!(vec.checksum(), checksum);
+
+        // The checksum depends on the position of the elements.
+        vec.replace(1, &5);
+        assert_ne!(vec.checksum(), checksum);
+        vec.set_raw(1, &2u64.try_to_vec().unwrap());
+        assert_eq!(vec.checksum(), checksum);
+        vec.swap_remove(0);
+        assert_eq!(vec.to_vec(), vec![3, 2]);
+        assert_ne!(vec.checksum(), checksum);
+        vec.swap_remove(0);
+        vec.swap_remove(0);
+        assert_eq!(vec.checksum(), 0);
+
+        vec.extend(vec![1u64, 2, 3]);
+        assert_eq!(vec.checksum(), checksum);
+        vec.clear();
+        assert_eq!(vec.checksum(), 0);
+        assert!(crate::mock::with_mocked_blockchain(|b| b.take_storage()).is_empty());
+    }
+
+    #[test]
+    pub fn test_checksum_written_eagerly() {
+        test_env::setup();
+        let mut vec = Vector::new_with_checksum(b"v".to_vec());
+        let before = env::storage_usage();
+        vec.extend(vec![1u64, 2, 3]);
+        let checksum = vec.checksum();
+        // The checksum key is already accounted for while the vector is alive.
+        let with_checksum = env::storage_usage() - before;
+        let state = vec.try_to_vec().unwrap();
+
+        let mut vec = Vector::<u64>::try_from_slice(&state).unwrap();
+        assert_eq!(vec.checksum(), checksum);
+        vec.clear();
+        assert!(crate::mock::with_mocked_blockchain(|b| b.take_storage()).is_empty());
+
+        test_env::setup();
+        let mut plain = Vector::new(b"v".to_vec());
+        let before = env::storage_usage();
+        plain.extend(vec![1u64, 2, 3]);
+        assert!(env::storage_usage() - before < with_checksum);
+        assert!(!env::storage_has_key(b"vc"));
+        // The checksum of a vector that doesn't keep it is computed from the elements.
+        assert_eq!(plain.checksum(), checksum);
+    }
+
+    #[test]
+    pub fn test_checksum_serialization() {
+        test_env::setup();
+        let mut plain = Vector::<u64>::new(b"v".to_vec());
+        plain.extend(vec![1u64, 2, 3]);
+        // Vectors without a checksum keep the serialized form of a length and a prefix.
+        assert_eq!(plain.try_to_vec().unwrap(), (3u64, b"v".to_vec()).try_to_vec().unwrap());
+
+        let mut checked = Vector::<u64>::new_with_checksum(b"c".to_vec());
+        checked.extend(vec![1u64, 2, 3]);
+        let state = checked.try_to_vec().unwrap();
+        let checked = Vector::<u64>::try_from_slice(&state).unwrap();
+        assert_eq!(checked.len(), 3);
+        assert!(env::storage_has_key(b"cc"));
+        assert_eq!(checked.checksum(), plain.checksum());
+
+        // A vector from before the upgrade gets the checksum of its current content.
+        let mut plain = Vector::<u64>::try_from_slice(&plain.try_to_vec().unwrap()).unwrap();
+        plain.enable_checksum();
+        assert_eq!(plain.checksum(), checked.checksum());
+        let plain = Vector::<u64>::try_from_slice(&plain.try_to_vec().unwrap()).unwrap();
+        assert_eq!(plain.checksum(), checked.checksum());
+    }
+
+    #[test]
+    pub fn test_set_raw() {
+        test_env::setup();
+        let mut vec = Vector::new(b"v".to_vec());
+        vec.push(&vec![0u8; 1000]);
+        vec.push(&vec![0u8; 1000]);
+
+        let gas_of = |f: &mut dyn FnMut()| {
+            let before = env::used_gas();
+            f();
+            env::used_gas().0 - before.0
+        };
+        let small = vec![1u8].try_to_vec().unwrap();
+        // `replace_raw` reads the evicted element into a register, `set_raw` doesn't.
+        let replace = gas_of(&mut || {
+            vec.replace_raw(0, &small);
+        });
+        let set = gas_of(&mut || vec.set_raw(1, &small));
+        assert!(set < replace, "{} < {}", set, replace);
+        assert_eq!(vec.to_vec(), vec![vec![1u8], vec![1u8]]);
+    }
+
     #[test]
     fn test_debug() {
         test_env::setup();
@@ -377,15 +1206,22 @@ mod tests {
         } else {
             assert_eq!(
                 format!("{:?}", vec),
-                format!("Vector {{ len: 5, prefix: {:?}, el: PhantomData }}", vec.prefix)
+                format!(
+                    "Vector {{ len: 5, prefix: {:?}, el: PhantomData, has_checksum: false }}",
+                    vec.prefix
+                )
             );
         }
 
         #[derive(Debug, BorshDeserialize)]
         struct WithoutBorshSerialize(u64);
 
-        let deserialize_only_vec =
-            Vector::<WithoutBorshSerialize> { len: vec.len(), prefix, el: Default::default() };
+        let deserialize_only_vec = Vector::<WithoutBorshSerialize> {
+            len: vec.len(),
+            prefix,
+            el: Default::default(),
+            has_checksum: false,
+        };
         let baseline: Vec<_> = baseline.into_iter().map(WithoutBorshSerialize).collect();
         if cfg!(feature = "expensive-debug") {
             assert_eq!(format!("{:#?}", deserialize_only_vec), format!("{:#?}", baseline));
@@ -393,7 +1229,7 @@ mod tests {
             assert_eq!(
                 format!("{:?}", deserialize_only_vec),
                 format!(
-                    "Vector {{ len: 5, prefix: {:?}, el: PhantomData }}",
+                    "Vector {{ len: 5, prefix: {:?}, el: PhantomData, has_checksum: false }}",
                     deserialize_only_vec.prefix
                 )
             );
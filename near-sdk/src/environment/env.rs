@@ -4,7 +4,6 @@
 //! through `callback_args`, `callback_args_vec`, `ext_contract`, `Promise`, and `PromiseOrValue`.
 
 use std::borrow::Borrow;
-#[cfg(not(target_arch = "wasm32"))]
 use std::cell::RefCell;
 use std::convert::TryFrom;
 use std::mem::size_of;
@@ -13,8 +12,11 @@ use std::panic as std_panic;
 use super::sys;
 #[cfg(not(target_arch = "wasm32"))]
 use crate::mock::MockedBlockchain;
+#[cfg(feature = "unstable")]
+use crate::types::CryptoHash;
 use crate::types::{
-    AccountId, Balance, BlockHeight, Gas, PromiseIndex, PromiseResult, PublicKey, StorageUsage,
+    AccountId, Balance, BlockHeight, CurveType, Gas, ParseAccountIdError, PromiseIndex,
+    PromiseResult, PublicKey, StorageUsage,
 };
 
 #[cfg(not(target_arch = "wasm32"))]
@@ -25,6 +27,15 @@ thread_local! {
          = RefCell::new(MockedBlockchain::default());
 }
 
+thread_local! {
+/// The account IDs of the current execution, read from the host on the first access. A contract
+/// executes one method per Wasm instance, so in Wasm the cache lives exactly as long as the
+/// execution. The mocked blockchain runs many executions on one thread, so
+/// `set_blockchain_interface` clears the cache when it installs a new context.
+    static CURRENT_ACCOUNT_ID: RefCell<Option<AccountId>> = RefCell::new(None);
+    static PREDECESSOR_ACCOUNT_ID: RefCell<Option<AccountId>> = RefCell::new(None);
+}
+
 const REGISTER_EXPECTED_ERR: &str =
     "Register was expected to have data because we just wrote it into it.";
 
@@ -38,11 +49,6 @@ const EVICTED_REGISTER: u64 = std::u64::MAX - 1;
 /// Key used to store the state of the contract.
 const STATE_KEY: &[u8] = b"STATE";
 
-/// The minimum length of a valid account ID.
-const MIN_ACCOUNT_ID_LEN: u64 = 2;
-/// The maximum length of a valid account ID.
-const MAX_ACCOUNT_ID_LEN: u64 = 64;
-
 /// A simple macro helper to read blob value coming from host's method.
 macro_rules! try_method_into_register {
     ( $method:ident ) => {{
@@ -81,7 +87,10 @@ macro_rules! method_into_register {
 pub fn set_blockchain_interface(blockchain_interface: MockedBlockchain) {
     BLOCKCHAIN_INTERFACE.with(|b| {
         *b.borrow_mut() = blockchain_interface;
-    })
+    });
+    CURRENT_ACCOUNT_ID.with(|cached| cached.borrow_mut().take());
+    PREDECESSOR_ACCOUNT_ID.with(|cached| cached.borrow_mut().take());
+    crate::collections::clear_registered_prefixes();
 }
 
 /// Implements panic hook that converts `PanicInfo` into a string and provides it through the
@@ -117,8 +126,17 @@ pub fn register_len(register_id: u64) -> Option<u64> {
 // # Context API #
 // ###############
 /// The id of the account that owns the current contract.
+///
+/// The id is read from the host once per execution, the later calls return a copy of it.
 pub fn current_account_id() -> AccountId {
-    assert_valid_account_id(method_into_register!(current_account_id))
+    CURRENT_ACCOUNT_ID.with(|cached| {
+        cached
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                assert_valid_account_id(method_into_register!(current_account_id))
+            })
+            .clone()
+    })
 }
 
 /// The id of the account that either signed the original transaction or issued the initial
@@ -134,8 +152,48 @@ pub fn signer_account_pk() -> PublicKey {
 
 /// The id of the account that was the previous contract in the chain of cross-contract calls.
 /// If this is the first contract, it is equal to `signer_account_id`.
+///
+/// The id is read from the host once per execution, the later calls return a copy of it.
 pub fn predecessor_account_id() -> AccountId {
-    assert_valid_account_id(method_into_register!(predecessor_account_id))
+    PREDECESSOR_ACCOUNT_ID.with(|cached| {
+        cached
+            .borrow_mut()
+            .get_or_insert_with(|| {
+                assert_valid_account_id(method_into_register!(predecessor_account_id))
+            })
+            .clone()
+    })
+}
+
+/// Returns `true` if the contract was called by itself, either from a callback or from a
+/// function call it scheduled on its own account.
+pub fn predecessor_is_current() -> bool {
+    predecessor_account_id() == current_account_id()
+}
+
+/// Same as [`predecessor_is_current`]. Both account ids are cached, so checking it in several
+/// guards of a method reads the host only once.
+pub fn caller_is_self() -> bool {
+    predecessor_is_current()
+}
+
+/// Panics unless the method was called by `expected`. The message names both accounts, so unlike
+/// `#[private]` it also works for checks against other accounts.
+pub fn assert_predecessor(expected: &AccountId) {
+    let predecessor = predecessor_account_id();
+    if &predecessor != expected {
+        panic(
+            format!("Method can only be called by {}, but was called by {}", expected, predecessor)
+                .as_bytes(),
+        )
+    }
+}
+
+/// Panics unless the method was called by `owner`, for the methods of ownable contracts.
+pub fn assert_owner(owner: &AccountId) {
+    if &predecessor_account_id() != owner {
+        panic(format!("Method can only be called by the owner {}", owner).as_bytes())
+    }
 }
 
 /// Helper function to convert and check the account ID from bytes from the runtime.
@@ -151,6 +209,29 @@ pub fn input() -> Option<Vec<u8>> {
     try_method_into_register!(input)
 }
 
+/// Deserializes the input to the contract call from JSON. The methods generated by
+/// `near_bindgen` panic on malformed input, this returns the error so that a method can handle
+/// it itself. A missing input is parsed as an empty one.
+pub fn parse_input_json<T: serde::de::DeserializeOwned>() -> Result<T, serde_json::Error> {
+    serde_json::from_slice(&input().unwrap_or_default())
+}
+
+/// Deserializes the input to the contract call from Borsh, see [`parse_input_json`].
+pub fn parse_input_borsh<T: borsh::BorshDeserialize>() -> std::io::Result<T> {
+    T::try_from_slice(&input().unwrap_or_default())
+}
+
+/// Deserializes the input to the contract call from JSON, or from Borsh if it is not valid JSON.
+/// Returns the Borsh error if neither works. The methods marked with `#[serializer(auto)]` parse
+/// their arguments with this.
+pub fn parse_input_json_or_borsh<T>() -> std::io::Result<T>
+where
+    T: serde::de::DeserializeOwned + borsh::BorshDeserialize,
+{
+    let input = input().unwrap_or_default();
+    serde_json::from_slice(&input).or_else(|_| T::try_from_slice(&input))
+}
+
 /// Current block index.
 pub fn block_index() -> BlockHeight {
     unsafe { sys::block_index() }
@@ -207,6 +288,29 @@ pub fn used_gas() -> Gas {
     Gas(unsafe { sys::used_gas() })
 }
 
+/// The gas that is left for the rest of the execution, `prepaid_gas() - used_gas()`. Both are read
+/// from the host on every call, so this reflects the gas used so far. Loops over a variable number
+/// of items can check it on every iteration and stop early, returning where they stopped.
+///
+/// # Examples
+/// ```no_run
+/// use near_sdk::{env, Gas};
+///
+/// fn process(items: &[u64]) -> usize {
+///     for (i, item) in items.iter().enumerate() {
+///         if env::gas_remaining() < Gas::from_tgas(5) {
+///             // Not enough gas left, continue from `i` in the next call.
+///             return i;
+///         }
+///         env::storage_write(&item.to_le_bytes(), b"processed");
+///     }
+///     items.len()
+/// }
+/// ```
+pub fn gas_remaining() -> Gas {
+    prepaid_gas() - used_gas()
+}
+
 // ############
 // # Math API #
 // ############
@@ -217,20 +321,87 @@ pub fn random_seed() -> Vec<u8> {
 
 /// Hashes the random sequence of bytes using sha256.
 pub fn sha256(value: &[u8]) -> Vec<u8> {
-    unsafe { sys::sha256(value.len() as _, value.as_ptr() as _, ATOMIC_OP_REGISTER) };
-    read_register(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR)
+    sha256_array(value).to_vec()
 }
 
 /// Hashes the random sequence of bytes using keccak256.
 pub fn keccak256(value: &[u8]) -> Vec<u8> {
-    unsafe { sys::keccak256(value.len() as _, value.as_ptr() as _, ATOMIC_OP_REGISTER) };
-    read_register(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR)
+    keccak256_array(value).to_vec()
 }
 
 /// Hashes the random sequence of bytes using keccak512.
 pub fn keccak512(value: &[u8]) -> Vec<u8> {
+    keccak512_array(value).to_vec()
+}
+
+/// Hashes the random sequence of bytes using sha256 and returns the hash without allocating.
+pub fn sha256_array(value: &[u8]) -> [u8; 32] {
+    unsafe { sys::sha256(value.len() as _, value.as_ptr() as _, ATOMIC_OP_REGISTER) };
+    let mut hash = [0u8; 32];
+    read_register_into(ATOMIC_OP_REGISTER, &mut hash);
+    hash
+}
+
+/// Hashes the random sequence of bytes using keccak256 and returns the hash without allocating.
+pub fn keccak256_array(value: &[u8]) -> [u8; 32] {
+    unsafe { sys::keccak256(value.len() as _, value.as_ptr() as _, ATOMIC_OP_REGISTER) };
+    let mut hash = [0u8; 32];
+    read_register_into(ATOMIC_OP_REGISTER, &mut hash);
+    hash
+}
+
+/// Hashes the random sequence of bytes using keccak512 and returns the hash without allocating.
+pub fn keccak512_array(value: &[u8]) -> [u8; 64] {
     unsafe { sys::keccak512(value.len() as _, value.as_ptr() as _, ATOMIC_OP_REGISTER) };
-    read_register(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR)
+    let mut hash = [0u8; 64];
+    read_register_into(ATOMIC_OP_REGISTER, &mut hash);
+    hash
+}
+
+/// Verifies an ed25519 `signature` of the `message` made with the key `public_key`. Returns `false`
+/// if the signature doesn't match or the signature or the public key is malformed.
+///
+/// The host function was added in protocol version 59, a contract that calls it can't be deployed
+/// to a network with an older protocol version, so it requires the `unstable` feature.
+#[cfg(feature = "unstable")]
+pub fn ed25519_verify(signature: &[u8; 64], message: &[u8], public_key: &[u8; 32]) -> bool {
+    unsafe {
+        sys::ed25519_verify(
+            signature.len() as _,
+            signature.as_ptr() as _,
+            message.len() as _,
+            message.as_ptr() as _,
+            public_key.len() as _,
+            public_key.as_ptr() as _,
+        ) == 1
+    }
+}
+
+/// Same as `ed25519_verify` but takes a `PublicKey`. Returns `false` if the key is not an
+/// ed25519 key.
+///
+/// Requires protocol version 59 and the `unstable` feature, see [`ed25519_verify`].
+#[cfg(feature = "unstable")]
+pub fn ed25519_verify_public_key(
+    signature: &[u8; 64],
+    message: &[u8],
+    public_key: &PublicKey,
+) -> bool {
+    if public_key.curve_type() != CurveType::ED25519 {
+        return false;
+    }
+    match <&[u8; 32]>::try_from(&public_key.as_bytes()[1..]) {
+        Ok(key_data) => ed25519_verify(signature, message, key_data),
+        Err(_) => false,
+    }
+}
+
+/// Reads the content of the register into the buffer, which must have exactly the length of the
+/// register.
+fn read_register_into(register_id: u64, buf: &mut [u8]) {
+    let len = register_len(register_id).expect(REGISTER_EXPECTED_ERR);
+    assert_eq!(len, buf.len() as u64, "Unexpected length of the register");
+    unsafe { sys::read_register(register_id, buf.as_mut_ptr() as _) };
 }
 
 // ################
@@ -307,6 +478,58 @@ pub fn promise_batch_then(promise_index: PromiseIndex, account_id: &AccountId) -
     }
 }
 
+/// Creates a yielded promise that calls `function_name` on the current account with `arguments`
+/// once it is resumed with [`promise_yield_resume`], or after it times out. Returns the data ID
+/// that is used to resume the promise and the index of the promise, which can be used as any other
+/// promise, e.g. returned with [`promise_return`].
+///
+/// The callback is attached the given `gas` and the unused gas is distributed according to
+/// `gas_weight`. It can read the payload of the resume as the only promise result, which is
+/// [`PromiseResult::Failed`] if the yield timed out.
+///
+/// The host function was added in protocol version 67, a contract that calls it can't be deployed
+/// to a network with an older protocol version, so it requires the `unstable` feature.
+#[cfg(feature = "unstable")]
+pub fn promise_yield_create(
+    function_name: &str,
+    arguments: &[u8],
+    gas: Gas,
+    gas_weight: u64,
+) -> (CryptoHash, PromiseIndex) {
+    let promise_index = unsafe {
+        sys::promise_yield_create(
+            function_name.len() as _,
+            function_name.as_ptr() as _,
+            arguments.len() as _,
+            arguments.as_ptr() as _,
+            gas.0,
+            gas_weight,
+            ATOMIC_OP_REGISTER,
+        )
+    };
+    let data_id = read_register(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR);
+    let data_id = CryptoHash::try_from(data_id.as_slice())
+        .unwrap_or_else(|_| panic(b"Data ID of the yielded promise is not 32 bytes long"));
+    (data_id, promise_index)
+}
+
+/// Resumes the yielded promise with the given `data_id`, passing `payload` to its callback.
+/// Returns `false` if there is no yielded promise with this data ID, for example because it was
+/// already resumed or it timed out.
+///
+/// Requires protocol version 67 and the `unstable` feature, see [`promise_yield_create`].
+#[cfg(feature = "unstable")]
+pub fn promise_yield_resume(data_id: &CryptoHash, payload: &[u8]) -> bool {
+    unsafe {
+        sys::promise_yield_resume(
+            data_id.len() as _,
+            data_id.as_ptr() as _,
+            payload.len() as _,
+            payload.as_ptr() as _,
+        ) == 1
+    }
+}
+
 pub fn promise_batch_action_create_account(promise_index: PromiseIndex) {
     unsafe { sys::promise_batch_action_create_account(promise_index) }
 }
@@ -345,6 +568,29 @@ pub fn promise_batch_action_transfer(promise_index: PromiseIndex, amount: Balanc
     unsafe { sys::promise_batch_action_transfer(promise_index, &amount as *const Balance as _) }
 }
 
+/// Transfers the attached deposit back to the predecessor, for payable methods that can't fulfill
+/// the request. Returns the index of the transfer promise, or `None` without creating a promise if
+/// no deposit was attached.
+///
+/// # Examples
+/// ```
+/// use near_sdk::env;
+///
+/// # near_sdk::testing_env!(near_sdk::test_utils::VMContextBuilder::new().attached_deposit(5).build());
+/// if env::refund_deposit_to_predecessor().is_some() {
+///     near_sdk::log!("The deposit of {} yoctoNEAR was refunded", env::attached_deposit());
+/// }
+/// ```
+pub fn refund_deposit_to_predecessor() -> Option<PromiseIndex> {
+    let amount = attached_deposit();
+    if amount == 0 {
+        return None;
+    }
+    let promise_index = promise_batch_create(&predecessor_account_id());
+    promise_batch_action_transfer(promise_index, amount);
+    Some(promise_index)
+}
+
 pub fn promise_batch_action_stake<P: Borrow<PublicKey>>(
     promise_index: PromiseIndex,
     amount: Balance,
@@ -485,6 +731,10 @@ pub fn value_return(value: &[u8]) {
 pub fn panic(message: &[u8]) -> ! {
     unsafe { sys::panic_utf8(message.len() as _, message.as_ptr() as _) }
 }
+/// Terminates the execution of the program with the message.
+pub fn panic_str(message: &str) -> ! {
+    panic(message.as_bytes())
+}
 /// Logs the string message message. This message is stored on chain.
 pub fn log_str(message: &str) {
     #[cfg(all(debug_assertions, not(target_arch = "wasm32")))]
@@ -530,6 +780,20 @@ pub fn storage_read(key: &[u8]) -> Option<Vec<u8>> {
         _ => unreachable!(),
     }
 }
+/// Reads the value stored under the given key into `buf`, reusing its allocation. Returns `false`
+/// and leaves `buf` unchanged if there is no value under the key.
+pub fn storage_read_into(key: &[u8], buf: &mut Vec<u8>) -> bool {
+    match unsafe { sys::storage_read(key.len() as _, key.as_ptr() as _, ATOMIC_OP_REGISTER) } {
+        0 => false,
+        1 => {
+            let len = register_len(ATOMIC_OP_REGISTER).expect(REGISTER_EXPECTED_ERR);
+            buf.resize(len as usize, 0);
+            read_register_into(ATOMIC_OP_REGISTER, buf);
+            true
+        }
+        _ => unreachable!(),
+    }
+}
 /// Removes the value stored under the given key.
 /// If key-value existed returns `true`, otherwise `false`.
 pub fn storage_remove(key: &[u8]) -> bool {
@@ -555,12 +819,43 @@ pub fn storage_has_key(key: &[u8]) -> bool {
 // ############################################
 // # Saving and loading of the contract state #
 // ############################################
-/// Load the state of the given object.
+/// Load the state of the given object. Returns `None` if the state was never written.
+///
+/// The state is stored with Borsh under the storage key `b"STATE"`, the same key that
+/// `#[near_bindgen]` uses, so the state can be read as a different type than the current contract
+/// struct. This is how a migration reads the state written by the previous version of the
+/// contract:
+/// ```
+/// use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+/// use near_sdk::{env, near_bindgen};
+///
+/// #[derive(BorshDeserialize)]
+/// struct OldContract {
+///     value: u32,
+/// }
+///
+/// #[near_bindgen]
+/// #[derive(BorshDeserialize, BorshSerialize, Default)]
+/// pub struct Contract {
+///     value: u64,
+///     owner: String,
+/// }
+///
+/// #[near_bindgen]
+/// impl Contract {
+///     #[init(ignore_state)]
+///     pub fn migrate() -> Self {
+///         let old: OldContract = env::state_read().expect("The contract is not initialized");
+///         Self { value: old.value.into(), owner: env::predecessor_account_id().into() }
+///     }
+/// }
+/// ```
 pub fn state_read<T: borsh::BorshDeserialize>() -> Option<T> {
     storage_read(STATE_KEY)
         .map(|data| T::try_from_slice(&data).expect("Cannot deserialize the contract state."))
 }
 
+/// Writes the state with Borsh under the storage key `b"STATE"`, replacing the previous state.
 pub fn state_write<T: borsh::BorshSerialize>(state: &T) {
     let data = state.try_to_vec().expect("Cannot serialize the contract state.");
     storage_write(STATE_KEY, &data);
@@ -583,38 +878,59 @@ pub fn storage_byte_cost() -> Balance {
     STORAGE_PRICE_PER_BYTE
 }
 
+/// The balance that has to be staked to store `bytes` bytes, `bytes * storage_byte_cost()`.
+pub fn required_storage_deposit(bytes: StorageUsage) -> Balance {
+    Balance::from(bytes) * storage_byte_cost()
+}
+
 // ##################
 // # Helper methods #
 // ##################
 
 /// Returns `true` if the given account ID is valid and `false` otherwise.
+///
+/// See [`AccountId::validate`] for the rules of a valid account ID.
 pub fn is_valid_account_id(account_id: &[u8]) -> bool {
-    if (account_id.len() as u64) < MIN_ACCOUNT_ID_LEN
-        || (account_id.len() as u64) > MAX_ACCOUNT_ID_LEN
-    {
-        return false;
-    }
-
-    // NOTE: We don't want to use Regex here, because it requires extra time to compile it.
-    // The valid account ID regex is /^(([a-z\d]+[-_])*[a-z\d]+\.)*([a-z\d]+[-_])*[a-z\d]+$/
-    // Instead the implementation is based on the previous character checks.
-
-    // We can safely assume that last char was a separator.
-    let mut last_char_is_separator = true;
+    crate::types::validate_account_id(account_id).is_ok()
+}
 
-    for c in account_id {
-        let current_char_is_separator = match *c {
-            b'a'..=b'z' | b'0'..=b'9' => false,
-            b'-' | b'_' | b'.' => true,
-            _ => return false,
-        };
-        if current_char_is_separator && last_char_is_separator {
-            return false;
-        }
-        last_char_is_separator = current_char_is_separator;
-    }
-    // The account can't end as separator.
-    !last_char_is_separator
+/// Derives a sub-account ID of `base` and an ED25519 public key from the given `label` and `seed`.
+/// The same inputs always produce the same pair, which allows factory contracts to know the
+/// account and the key before creating the account.
+///
+/// The derivation is:
+/// - `hash = sha256(seed)`;
+/// - the account ID is `{label}-{hex(hash[..4])}.{base}`, e.g. `token-1a2b3c4d.factory.near`;
+/// - the public key data is `sha256(hash ++ account_id)`.
+///
+/// Note that the key is not generated from a secret key, so nobody can sign transactions with it.
+/// It can be used as a deterministic placeholder key that the factory later replaces.
+///
+/// Returns an error if the resulting account ID is invalid, for example because `label` contains
+/// invalid characters or the account ID is too long.
+///
+/// # Examples
+/// ```
+/// use near_sdk::{env, AccountId};
+///
+/// let factory: AccountId = "factory.near".parse().unwrap();
+/// let (account_id, public_key) = env::derive_sub_account(&factory, "token", b"seed").unwrap();
+/// assert!(account_id.is_sub_account_of(&factory));
+/// assert_eq!(env::derive_sub_account(&factory, "token", b"seed").unwrap(), (account_id, public_key));
+/// ```
+pub fn derive_sub_account(
+    base: &AccountId,
+    label: &str,
+    seed: &[u8],
+) -> Result<(AccountId, PublicKey), ParseAccountIdError> {
+    let hash = sha256(seed);
+    let suffix: String = hash[..4].iter().map(|b| format!("{:02x}", b)).collect();
+    let account_id: AccountId = format!("{}-{}.{}", label, suffix, base).parse()?;
+
+    let mut key_data = vec![CurveType::ED25519 as u8];
+    key_data.extend(sha256(&[hash.as_slice(), account_id.as_bytes()].concat()));
+    let public_key = PublicKey::try_from(key_data).unwrap_or_else(|_| unreachable!());
+    Ok((account_id, public_key))
 }
 
 #[cfg(test)]
@@ -696,6 +1012,137 @@ mod tests {
         }
     }
 
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn test_promise_yield() {
+        crate::test_utils::test_env::setup();
+        let (data_id, promise_index) =
+            promise_yield_create("on_resume", b"{\"id\":1}", Gas(5_000_000_000_000), 1);
+        let (other_data_id, _) = promise_yield_create("on_resume", b"{\"id\":2}", Gas(0), 0);
+        assert_ne!(data_id, other_data_id);
+
+        assert!(promise_yield_resume(&data_id, b"payload"));
+        // A yield can only be resumed once.
+        assert!(!promise_yield_resume(&data_id, b"other payload"));
+        assert!(!promise_yield_resume(&[7; 32], b"payload"));
+
+        let yields = crate::mock::with_mocked_blockchain(|b| b.yields());
+        assert_eq!(yields.len(), 2);
+        assert_eq!(yields[0].data_id, data_id);
+        assert_eq!(yields[0].promise_index, promise_index);
+        assert_eq!(yields[0].function_name, "on_resume");
+        assert_eq!(yields[0].arguments, b"{\"id\":1}".to_vec());
+        assert_eq!(yields[0].gas, Gas(5_000_000_000_000));
+        assert_eq!(yields[0].gas_weight, 1);
+        assert_eq!(yields[0].payload, Some(b"payload".to_vec()));
+        assert_eq!(yields[1].payload, None);
+
+        // The callbacks are scheduled as function calls on the current account.
+        let receipts = crate::test_utils::get_created_receipts();
+        assert_eq!(receipts.len(), 2);
+    }
+
+    fn to_hex(bytes: &[u8]) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_hash_arrays() {
+        crate::test_utils::test_env::setup();
+        assert_eq!(
+            to_hex(&sha256_array(b"")),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            to_hex(&sha256_array(b"abc")),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        assert_eq!(
+            to_hex(&keccak256_array(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+        assert_eq!(
+            to_hex(&keccak512_array(b"")),
+            concat!(
+                "0eab42de4c3ceb9235fc91acffe746b29c29a8c366b7c60e4e67c466f36a4304",
+                "c00fa9caf9d87976ba469bcbe06713b435f091ef2769fb160cdab33d3670680e"
+            )
+        );
+        assert_eq!(sha256(b"abc"), sha256_array(b"abc").to_vec());
+        assert_eq!(keccak256(b"abc"), keccak256_array(b"abc").to_vec());
+        assert_eq!(keccak512(b"abc"), keccak512_array(b"abc").to_vec());
+    }
+
+    #[cfg(feature = "unstable")]
+    fn from_hex(hex: &str) -> Vec<u8> {
+        (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap()).collect()
+    }
+
+    #[test]
+    #[cfg(feature = "unstable")]
+    fn test_ed25519_verify() {
+        crate::test_utils::test_env::setup();
+        // Test vector 2 from RFC 8032.
+        let public_key: [u8; 32] = <[u8; 32]>::try_from(from_hex(
+            "3d4017c3e843895a92b70aa74d1b7ebc9c982ccf2ec4968cc0cd55f12af4660c",
+        ))
+        .unwrap();
+        let mut signature: [u8; 64] = <[u8; 64]>::try_from(from_hex(concat!(
+            "92a009a9f0d4cab8720e820b5f642540a2b27b5416503f8fb3762223ebdb69da",
+            "085ac1e43e15996e458f3613d0f11d8c387b2eaeb4302aeeb00d291612bb0c00"
+        )))
+        .unwrap();
+        let message = [0x72];
+        assert!(ed25519_verify(&signature, &message, &public_key));
+        assert!(!ed25519_verify(&signature, &[0x73], &public_key));
+
+        let typed_key =
+            PublicKey::try_from([&[CurveType::ED25519 as u8][..], &public_key].concat()).unwrap();
+        assert!(ed25519_verify_public_key(&signature, &message, &typed_key));
+        let secp_key =
+            PublicKey::try_from([&[CurveType::SECP256K1 as u8][..], &[1; 64]].concat()).unwrap();
+        assert!(!ed25519_verify_public_key(&signature, &message, &secp_key));
+
+        signature[0] ^= 1;
+        assert!(!ed25519_verify(&signature, &message, &public_key));
+        // Not a valid point on the curve.
+        signature[0] ^= 1;
+        assert!(!ed25519_verify(&signature, &message, &[0xff; 32]));
+    }
+
+    #[test]
+    fn test_storage_read_into() {
+        crate::test_utils::test_env::setup();
+        storage_write(b"k", b"value");
+        let mut buf = b"longer buffer".to_vec();
+        assert!(storage_read_into(b"k", &mut buf));
+        assert_eq!(buf, b"value");
+        assert!(!storage_read_into(b"missing", &mut buf));
+        assert_eq!(buf, b"value");
+    }
+
+    #[test]
+    fn test_derive_sub_account() {
+        let factory: AccountId = "factory.near".parse().unwrap();
+        let (account_id, public_key) = derive_sub_account(&factory, "token", b"seed").unwrap();
+        assert!(account_id.is_sub_account_of(&factory));
+        assert!(account_id.as_str().starts_with("token-"));
+        assert_eq!(public_key.curve_type(), CurveType::ED25519);
+
+        // Same inputs produce the same pair.
+        assert_eq!(
+            derive_sub_account(&factory, "token", b"seed").unwrap(),
+            (account_id.clone(), public_key.clone())
+        );
+        // Different seed produces a different pair.
+        let (other_id, other_key) = derive_sub_account(&factory, "token", b"seed2").unwrap();
+        assert_ne!(other_id, account_id);
+        assert_ne!(other_key, public_key);
+
+        assert!(derive_sub_account(&factory, "Token", b"seed").is_err());
+        assert!(derive_sub_account(&factory, &"a".repeat(60), b"seed").is_err());
+    }
+
     #[test]
     fn test_is_valid_account_id_binary() {
         assert!(!is_valid_account_id(&[]));
@@ -704,4 +1151,221 @@ mod tests {
         assert!(!is_valid_account_id(&[0, 1, 2]));
         assert!(is_valid_account_id(b"near"));
     }
+
+    #[test]
+    fn test_state_read_write() {
+        use borsh::{BorshDeserialize, BorshSerialize};
+
+        #[derive(BorshSerialize)]
+        struct OldState {
+            value: u32,
+            name: String,
+        }
+
+        #[derive(BorshSerialize, BorshDeserialize, Debug, PartialEq)]
+        struct NewState {
+            value: u32,
+            name: String,
+            flag: bool,
+        }
+
+        crate::test_utils::test_env::setup();
+        assert!(!state_exists());
+        assert_eq!(state_read::<NewState>(), None);
+
+        state_write(&OldState { value: 3, name: "old".to_string() });
+        assert!(state_exists());
+        assert_eq!(storage_read(b"STATE"), Some(vec![3, 0, 0, 0, 3, 0, 0, 0, b'o', b'l', b'd']));
+
+        // Read the old layout under a different type, as a migration would.
+        let (value, name): (u32, String) = state_read().unwrap();
+        let new = NewState { value: value * 2, name, flag: true };
+        state_write(&new);
+        assert_eq!(state_read::<NewState>(), Some(new));
+    }
+
+    #[test]
+    #[should_panic(expected = "ProhibitedInView { method_name: \"storage_write\" }")]
+    fn test_state_write_in_view_context() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        // A change method called in a view context is not able to silently drop its state changes:
+        // the host aborts the call on the first write, like the state write at the end of the
+        // `#[near_bindgen]` wrapper.
+        testing_env!(VMContextBuilder::new().is_view(true).build());
+        assert_eq!(state_read::<u64>(), None);
+        state_write(&5u64);
+    }
+
+    #[test]
+    fn test_required_storage_deposit() {
+        assert_eq!(required_storage_deposit(1000), 1000 * storage_byte_cost());
+        assert_eq!(required_storage_deposit(0), 0);
+        // The largest storage usage doesn't overflow the balance.
+        assert_eq!(
+            required_storage_deposit(StorageUsage::MAX),
+            Balance::from(StorageUsage::MAX) * STORAGE_PRICE_PER_BYTE
+        );
+    }
+
+    #[test]
+    fn test_gas_remaining() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        testing_env!(VMContextBuilder::new().prepaid_gas(Gas::from_tgas(300)).build());
+        let before = gas_remaining();
+        assert!(before <= Gas::from_tgas(300));
+        for i in 0..10u64 {
+            storage_write(&i.to_le_bytes(), b"value");
+        }
+        let after = gas_remaining();
+        assert!(after < before);
+    }
+
+    #[test]
+    fn test_parse_input() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        #[derive(serde::Deserialize, Debug, PartialEq)]
+        struct Args {
+            value: u32,
+        }
+
+        testing_env!(VMContextBuilder::new().input(br#"{"value": 5}"#.to_vec()).build());
+        assert_eq!(parse_input_json::<Args>().unwrap(), Args { value: 5 });
+        assert!(parse_input_json::<u32>().is_err());
+
+        testing_env!(VMContextBuilder::new().input(br#"{"value": "5"}"#.to_vec()).build());
+        assert!(parse_input_json::<Args>().is_err());
+        testing_env!(VMContextBuilder::new().input(b"{".to_vec()).build());
+        assert!(parse_input_json::<Args>().is_err());
+        testing_env!(VMContextBuilder::new().build());
+        assert!(parse_input_json::<Args>().is_err());
+
+        testing_env!(VMContextBuilder::new().input(vec![5, 0, 0, 0]).build());
+        assert_eq!(parse_input_borsh::<u32>().unwrap(), 5);
+        assert!(parse_input_borsh::<u64>().is_err());
+    }
+
+    #[test]
+    fn test_parse_input_json_or_borsh() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+        use borsh::{BorshDeserialize, BorshSerialize};
+
+        #[derive(serde::Deserialize, BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+        struct Args {
+            value: u32,
+            name: String,
+        }
+        let expected = Args { value: 5, name: "bob".to_string() };
+
+        testing_env!(VMContextBuilder::new()
+            .input(br#"{"value": 5, "name": "bob"}"#.to_vec())
+            .build());
+        assert_eq!(parse_input_json_or_borsh::<Args>().unwrap(), expected);
+
+        testing_env!(VMContextBuilder::new().input(expected.try_to_vec().unwrap()).build());
+        assert_eq!(parse_input_json_or_borsh::<Args>().unwrap(), expected);
+
+        testing_env!(VMContextBuilder::new().input(b"{".to_vec()).build());
+        assert!(parse_input_json_or_borsh::<Args>().is_err());
+    }
+
+    #[test]
+    fn test_assert_predecessor() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        let bob: AccountId = "bob.near".parse().unwrap();
+        testing_env!(VMContextBuilder::new().predecessor_account_id(bob.clone()).build());
+        assert_predecessor(&bob);
+        assert_owner(&bob);
+        assert!(!predecessor_is_current());
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(bob.clone())
+            .predecessor_account_id(bob)
+            .build());
+        assert!(predecessor_is_current());
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Method can only be called by alice.near, but was called by bob.near"
+    )]
+    fn test_assert_predecessor_mismatch() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id("bob.near".parse().unwrap()));
+        assert_predecessor(&"alice.near".parse().unwrap());
+    }
+
+    #[test]
+    #[should_panic(expected = "Method can only be called by the owner alice.near")]
+    fn test_assert_owner_mismatch() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id("bob.near".parse().unwrap()));
+        assert_owner(&"alice.near".parse().unwrap());
+    }
+
+    #[test]
+    fn test_account_ids_cached() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(alice.clone())
+            .predecessor_account_id(bob.clone())
+            .build());
+        assert_eq!(current_account_id(), alice);
+        assert_eq!(predecessor_account_id(), bob);
+
+        // The mocked host charges gas for every call, so the cached reads don't use any.
+        let used_gas_cost = {
+            let before = used_gas();
+            used_gas().0 - before.0
+        };
+        let before = used_gas();
+        assert_eq!(current_account_id(), alice);
+        assert_eq!(predecessor_account_id(), bob);
+        assert!(!predecessor_is_current());
+        assert!(!caller_is_self());
+        assert_eq!(used_gas().0 - before.0, used_gas_cost);
+
+        // A new context replaces the cached ids.
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(bob.clone())
+            .predecessor_account_id(bob.clone())
+            .build());
+        assert_eq!(current_account_id(), bob);
+        assert_eq!(predecessor_account_id(), bob);
+        assert!(caller_is_self());
+    }
+
+    #[test]
+    fn test_refund_deposit_to_predecessor() {
+        use crate::test_utils::{get_created_receipts, get_transfers, VMContextBuilder};
+        use crate::testing_env;
+
+        let bob: AccountId = "bob.near".parse().unwrap();
+        testing_env!(VMContextBuilder::new()
+            .predecessor_account_id(bob.clone())
+            .attached_deposit(10)
+            .build());
+        assert!(refund_deposit_to_predecessor().is_some());
+        assert_eq!(get_transfers(), vec![(bob.clone(), 10)]);
+
+        testing_env!(VMContextBuilder::new().predecessor_account_id(bob).build());
+        assert_eq!(refund_deposit_to_predecessor(), None);
+        assert!(get_created_receipts().is_empty());
+    }
 }
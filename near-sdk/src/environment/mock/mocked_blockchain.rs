@@ -1,10 +1,10 @@
 use crate::test_utils::VMContextBuilder;
-use crate::types::{Balance, PromiseResult};
+use crate::types::{Balance, CryptoHash, Gas, PromiseResult};
 use crate::RuntimeFeesConfig;
 use near_vm_logic::mocks::mock_external::{MockedExternal, Receipt};
 use near_vm_logic::mocks::mock_memory::MockedMemory;
 use near_vm_logic::types::PromiseResult as VmPromiseResult;
-use near_vm_logic::{External, MemoryLike, VMConfig, VMContext, VMLogic, VMOutcome};
+use near_vm_logic::{External, MemoryLike, VMConfig, VMContext, VMLogic, VMLogicError, VMOutcome};
 use std::cell::RefCell;
 use std::collections::HashMap;
 
@@ -15,11 +15,66 @@ use std::collections::HashMap;
 /// message.
 pub struct MockedBlockchain {
     logic: RefCell<VMLogic<'static>>,
+    // The context the logic was created with, used to recreate it in `set_promise_results`.
+    context: VMContext,
+    // The version of `VMLogic` we depend on doesn't support yielded promises, so they are mocked here.
+    yields: RefCell<Vec<MockedYield>>,
+    // Method names and sizes of the values they returned, see `crate::record_return_size`.
+    return_sizes: Vec<(String, usize)>,
+    // Number of logs that were emitted before the last `clear_logs`.
+    cleared_logs: usize,
     // We keep ownership over logic fixture so that references in `VMLogic` are valid.
     #[allow(dead_code)]
     logic_fixture: LogicFixture,
 }
 
+/// A yielded promise created with [`env::promise_yield_create`](crate::env::promise_yield_create)
+/// and recorded by the [`MockedBlockchain`].
+///
+/// The mock doesn't execute the callback when the yield is resumed. To test the callback, set up a
+/// new testing environment with the `payload` as the only promise result and call the method.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MockedYield {
+    /// The data ID that is used to resume the yield.
+    pub data_id: CryptoHash,
+    /// The index of the promise that calls the callback.
+    pub promise_index: u64,
+    /// The name of the method on the current account that is called when the yield is resumed.
+    pub function_name: String,
+    /// The arguments of the callback.
+    pub arguments: Vec<u8>,
+    /// The gas attached to the callback.
+    pub gas: Gas,
+    /// The weight of unused gas given to the callback.
+    pub gas_weight: u64,
+    /// The payload the yield was resumed with, `None` if it was not resumed yet.
+    pub payload: Option<Vec<u8>>,
+}
+
+/// A copy of the storage of a [`MockedBlockchain`], taken with [`MockedBlockchain::snapshot`] and
+/// put back with [`MockedBlockchain::restore`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StorageSnapshot {
+    storage: HashMap<Vec<u8>, Vec<u8>>,
+}
+
+impl StorageSnapshot {
+    /// Returns the value stored under `key` when the snapshot was taken.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        self.storage.get(key).map(Vec::as_slice)
+    }
+
+    /// Returns the number of keys in the snapshot.
+    pub fn len(&self) -> usize {
+        self.storage.len()
+    }
+
+    /// Returns `true` if the storage was empty when the snapshot was taken.
+    pub fn is_empty(&self) -> bool {
+        self.storage.is_empty()
+    }
+}
+
 impl Default for MockedBlockchain {
     fn default() -> Self {
         MockedBlockchain::new(
@@ -66,7 +121,7 @@ impl MockedBlockchain {
         let logic = unsafe {
             VMLogic::new_with_protocol_version(
                 &mut *(logic_fixture.ext.as_mut() as *mut dyn External),
-                context,
+                context.clone(),
                 &*(logic_fixture.config.as_mut() as *const VMConfig),
                 &*(logic_fixture.fees_config.as_mut() as *const RuntimeFeesConfig),
                 &*(logic_fixture.promise_results.as_ref().as_slice() as *const [VmPromiseResult]),
@@ -77,13 +132,56 @@ impl MockedBlockchain {
         };
 
         let logic = RefCell::new(logic);
-        Self { logic, logic_fixture }
+        Self {
+            logic,
+            context,
+            yields: RefCell::new(vec![]),
+            return_sizes: vec![],
+            cleared_logs: 0,
+            logic_fixture,
+        }
+    }
+
+    /// Replaces the results of the promises that the current method is a callback of, which are
+    /// returned by [`env::promise_results_count`](crate::env::promise_results_count) and
+    /// [`env::promise_result`](crate::env::promise_result).
+    ///
+    /// The storage is kept, but the logs, the used gas and the created receipts are reset, so
+    /// stage the results before calling the callback.
+    pub fn set_promise_results(&mut self, promise_results: Vec<PromiseResult>) {
+        let fixture = &mut self.logic_fixture;
+        let mut blockchain = MockedBlockchain::new(
+            self.context.clone(),
+            std::mem::take(&mut *fixture.config),
+            std::mem::take(&mut *fixture.fees_config),
+            promise_results,
+            std::mem::take(&mut fixture.ext.fake_trie),
+            std::mem::take(&mut fixture.ext.validators),
+            Some(std::mem::replace(&mut fixture.memory, Box::new(MockedMemory {}))),
+        );
+        blockchain.yields = std::mem::take(&mut self.yields);
+        blockchain.return_sizes = std::mem::take(&mut self.return_sizes);
+        *self = blockchain;
     }
 
     pub fn take_storage(&mut self) -> HashMap<Vec<u8>, Vec<u8>> {
         std::mem::take(&mut self.logic_fixture.ext.fake_trie)
     }
 
+    /// Returns a copy of the storage, which [`restore`](Self::restore) can put back. This lets a
+    /// test check that an operation that panicked halfway didn't persist any of its writes,
+    /// which the runtime discards together with the failed execution.
+    pub fn snapshot(&self) -> StorageSnapshot {
+        StorageSnapshot { storage: self.logic_fixture.ext.fake_trie.clone() }
+    }
+
+    /// Replaces the storage with the snapshot. Only the storage is restored, the logs, the used
+    /// gas, the created receipts and the value of
+    /// [`env::storage_usage`](crate::env::storage_usage) are kept.
+    pub fn restore(&mut self, snapshot: StorageSnapshot) {
+        self.logic_fixture.ext.fake_trie = snapshot.storage;
+    }
+
     pub fn created_receipts(&self) -> &Vec<Receipt> {
         self.logic_fixture.ext.get_receipt_create_calls()
     }
@@ -95,8 +193,144 @@ impl MockedBlockchain {
         self.logic.borrow_mut().gas(gas_amount).unwrap()
     }
 
+    /// Returns the logs emitted since this blockchain was set up, or since the last call to
+    /// [`clear_logs`](Self::clear_logs), in the order they were emitted.
     pub fn logs(&self) -> Vec<String> {
-        self.logic.borrow().clone_outcome().logs
+        let mut logs = self.logic.borrow().clone_outcome().logs;
+        logs.drain(..self.cleared_logs);
+        logs
+    }
+
+    /// Clears the logs returned by [`logs`](Self::logs).
+    pub fn clear_logs(&mut self) {
+        self.cleared_logs = self.logic.borrow().clone_outcome().logs.len();
+    }
+
+    /// Returns the gas used since this blockchain was set up. The host functions are charged with
+    /// the costs of the [`VMConfig`] the blockchain was created with, the storage functions with a
+    /// base cost plus a cost per byte of the key and the value, so a test can compare the gas two
+    /// implementations of a method use.
+    pub fn gas_used(&self) -> Gas {
+        Gas(self.logic.borrow().clone_outcome().used_gas)
+    }
+
+    /// Returns the yielded promises created in this blockchain, including whether they were
+    /// resumed.
+    pub fn yields(&self) -> Vec<MockedYield> {
+        self.yields.borrow().clone()
+    }
+
+    /// Returns the method names and the sizes of the values they returned, recorded for the
+    /// `#[near_bindgen]` methods with the `debug-return-size` feature.
+    pub fn return_sizes(&self) -> Vec<(String, usize)> {
+        self.return_sizes.clone()
+    }
+
+    pub(crate) fn record_return_size(&mut self, method_name: &str, size: usize) {
+        self.return_sizes.push((method_name.to_string(), size));
+    }
+
+    fn read_memory(&self, ptr: u64, len: u64) -> Vec<u8> {
+        let mut buf = vec![0u8; len as usize];
+        self.logic_fixture.memory.read_memory(ptr, &mut buf);
+        buf
+    }
+
+    /// Mocks the yield with a function call promise to the current account, which is what the
+    /// runtime executes once the yield is resumed.
+    #[allow(clippy::too_many_arguments)]
+    fn promise_yield_create(
+        &self,
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        register_id: u64,
+    ) -> Result<u64, VMLogicError> {
+        let mut logic = self.logic.borrow_mut();
+        let mut yields = self.yields.borrow_mut();
+
+        logic.current_account_id(register_id)?;
+        let mut account_id = vec![0u8; logic.register_len(register_id)? as usize];
+        logic.read_register(register_id, account_id.as_mut_ptr() as _)?;
+        let promise_index =
+            logic.promise_batch_create(account_id.len() as _, account_id.as_ptr() as _)?;
+        let deposit: Balance = 0;
+        logic.promise_batch_action_function_call(
+            promise_index,
+            function_name_len,
+            function_name_ptr,
+            arguments_len,
+            arguments_ptr,
+            &deposit as *const Balance as _,
+            gas,
+        )?;
+
+        let mut data_id = CryptoHash::default();
+        data_id[..8].copy_from_slice(&(yields.len() as u64 + 1).to_le_bytes());
+        logic.wrapped_internal_write_register(register_id, &data_id)?;
+        let function_name = self.read_memory(function_name_ptr, function_name_len);
+        yields.push(MockedYield {
+            data_id,
+            promise_index,
+            function_name: String::from_utf8(function_name).expect("Invalid UTF-8 method name"),
+            arguments: self.read_memory(arguments_ptr, arguments_len),
+            gas: Gas(gas),
+            gas_weight,
+            payload: None,
+        });
+        Ok(promise_index)
+    }
+
+    /// Mocks the signature verification, which is not available in `VMLogic` yet. Returns `0` if
+    /// the signature or the public key is malformed.
+    fn ed25519_verify(
+        &self,
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> u64 {
+        use ed25519_dalek::Verifier;
+        use std::convert::TryFrom;
+        let signature = self.read_memory(sig_ptr, sig_len);
+        let message = self.read_memory(msg_ptr, msg_len);
+        let public_key = self.read_memory(pub_key_ptr, pub_key_len);
+        let signature = match ed25519_dalek::Signature::try_from(&signature[..]) {
+            Ok(signature) => signature,
+            Err(_) => return 0,
+        };
+        match ed25519_dalek::PublicKey::from_bytes(&public_key) {
+            Ok(public_key) => public_key.verify(&message, &signature).is_ok() as u64,
+            Err(_) => 0,
+        }
+    }
+
+    fn promise_yield_resume(
+        &self,
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64,
+    ) -> u32 {
+        let data_id = self.read_memory(data_id_ptr, data_id_len);
+        let payload = self.read_memory(payload_ptr, payload_len);
+        match self
+            .yields
+            .borrow_mut()
+            .iter_mut()
+            .find(|y| y.data_id[..] == data_id[..] && y.payload.is_none())
+        {
+            Some(y) => {
+                y.payload = Some(payload);
+                1
+            }
+            None => 0,
+        }
     }
 }
 
@@ -280,6 +514,54 @@ mod mock_chain {
         with_mock_interface(|b| b.promise_batch_then(promise_index, account_id_len, account_id_ptr))
     }
     #[no_mangle]
+    extern "C" fn promise_yield_create(
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        register_id: u64,
+    ) -> u64 {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow()
+                .promise_yield_create(
+                    function_name_len,
+                    function_name_ptr,
+                    arguments_len,
+                    arguments_ptr,
+                    gas,
+                    gas_weight,
+                    register_id,
+                )
+                .unwrap()
+        })
+    }
+    #[no_mangle]
+    extern "C" fn ed25519_verify(
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> u64 {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().ed25519_verify(sig_len, sig_ptr, msg_len, msg_ptr, pub_key_len, pub_key_ptr)
+        })
+    }
+    #[no_mangle]
+    extern "C" fn promise_yield_resume(
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64,
+    ) -> u32 {
+        BLOCKCHAIN_INTERFACE.with(|b| {
+            b.borrow().promise_yield_resume(data_id_len, data_id_ptr, payload_len, payload_ptr)
+        })
+    }
+    #[no_mangle]
     extern "C" fn promise_batch_action_create_account(promise_index: u64) {
         with_mock_interface(|b| b.promise_batch_action_create_account(promise_index))
     }
@@ -441,3 +723,126 @@ mod mock_chain {
         with_mock_interface(|b| b.validator_total_stake(stake_ptr))
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use crate::env;
+    use crate::mock::with_mocked_blockchain;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_logs() {
+        test_env::setup();
+        env::log_str("first");
+        env::log_str("second");
+        assert_eq!(with_mocked_blockchain(|b| b.logs()), vec!["first", "second"]);
+
+        with_mocked_blockchain(|b| b.clear_logs());
+        assert!(with_mocked_blockchain(|b| b.logs()).is_empty());
+        env::log_str("third");
+        assert_eq!(with_mocked_blockchain(|b| b.logs()), vec!["third"]);
+        assert_eq!(crate::test_utils::get_logs(), vec!["third"]);
+    }
+
+    #[test]
+    fn test_created_receipts() {
+        test_env::setup();
+        env::promise_batch_create(&"bob.near".parse().unwrap());
+        env::promise_batch_create(&"carol.near".parse().unwrap());
+        assert_eq!(with_mocked_blockchain(|b| b.created_receipts().len()), 2);
+    }
+
+    #[test]
+    fn test_gas_used() {
+        test_env::setup();
+        let before = with_mocked_blockchain(|b| b.gas_used());
+        env::log_str("log");
+        let after = with_mocked_blockchain(|b| b.gas_used());
+        assert!(after > before);
+        assert!(env::used_gas() >= after);
+    }
+
+    #[test]
+    fn test_storage_gas() {
+        test_env::setup();
+        let gas_of = |f: &dyn Fn()| {
+            let before = with_mocked_blockchain(|b| b.gas_used());
+            f();
+            with_mocked_blockchain(|b| b.gas_used()).0 - before.0
+        };
+        let small_write = gas_of(&|| {
+            env::storage_write(b"small", &[0; 10]);
+        });
+        let large_write = gas_of(&|| {
+            env::storage_write(b"large", &[0; 1000]);
+        });
+        assert!(large_write > small_write);
+
+        let large_read = gas_of(&|| {
+            env::storage_read(b"large");
+        });
+        assert!(large_read > 0);
+        assert!(large_read < large_write);
+
+        let remove = gas_of(&|| {
+            env::storage_remove(b"large");
+        });
+        assert!(remove > 0);
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        test_env::setup();
+        env::storage_write(b"balance", b"10");
+        let snapshot = with_mocked_blockchain(|b| b.snapshot());
+        assert_eq!(snapshot.get(b"balance"), Some(&b"10"[..]));
+        assert_eq!(snapshot.len(), 1);
+
+        // A transfer that panics between its two writes.
+        let result = std::panic::catch_unwind(|| {
+            env::storage_write(b"balance", b"0");
+            env::storage_write(b"receiver", b"10");
+            env::panic(b"Failed after the first writes");
+        });
+        assert!(result.is_err());
+        assert_eq!(env::storage_read(b"receiver"), Some(b"10".to_vec()));
+
+        with_mocked_blockchain(|b| b.restore(snapshot.clone()));
+        assert_eq!(with_mocked_blockchain(|b| b.snapshot()), snapshot);
+        assert_eq!(env::storage_read(b"balance"), Some(b"10".to_vec()));
+        assert!(!env::storage_has_key(b"receiver"));
+    }
+
+    #[test]
+    fn test_set_promise_results() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::{testing_env, PromiseResult};
+
+        // Reads the result like the wrapper of a method with a `#[callback] x: u64` argument.
+        fn on_value() -> u64 {
+            match env::promise_result(0) {
+                PromiseResult::Successful(data) => serde_json::from_slice(&data).unwrap(),
+                _ => panic!("Callback computation 0 was not successful"),
+            }
+        }
+
+        testing_env!(VMContextBuilder::new()
+            .current_account_id("alice.near".parse().unwrap())
+            .build());
+        env::storage_write(b"key", b"value");
+        assert_eq!(env::promise_results_count(), 0);
+
+        with_mocked_blockchain(|b| {
+            b.set_promise_results(vec![
+                PromiseResult::Successful(b"42".to_vec()),
+                PromiseResult::Failed,
+            ])
+        });
+        assert_eq!(env::promise_results_count(), 2);
+        assert_eq!(on_value(), 42);
+        assert_eq!(env::promise_result(1), PromiseResult::Failed);
+        assert_eq!(env::storage_read(b"key"), Some(b"value".to_vec()));
+        assert_eq!(env::current_account_id().as_str(), "alice.near");
+    }
+}
@@ -1,6 +1,6 @@
 mod mocked_blockchain;
 
-pub use self::mocked_blockchain::MockedBlockchain;
+pub use self::mocked_blockchain::{MockedBlockchain, MockedYield, StorageSnapshot};
 
 /// Perform function on a mutable reference to the [`MockedBlockchain`]. This can only be used
 /// inside tests.
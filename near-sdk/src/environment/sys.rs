@@ -31,6 +31,16 @@ extern "C" {
     pub fn sha256(value_len: u64, value_ptr: u64, register_id: u64);
     pub fn keccak256(value_len: u64, value_ptr: u64, register_id: u64);
     pub fn keccak512(value_len: u64, value_ptr: u64, register_id: u64);
+    // Available from protocol version 59, see `env::ed25519_verify`.
+    #[cfg(feature = "unstable")]
+    pub fn ed25519_verify(
+        sig_len: u64,
+        sig_ptr: u64,
+        msg_len: u64,
+        msg_ptr: u64,
+        pub_key_len: u64,
+        pub_key_ptr: u64,
+    ) -> u64;
     // #####################
     // # Miscellaneous API #
     // #####################
@@ -68,6 +78,24 @@ extern "C" {
     pub fn promise_and(promise_idx_ptr: u64, promise_idx_count: u64) -> u64;
     pub fn promise_batch_create(account_id_len: u64, account_id_ptr: u64) -> u64;
     pub fn promise_batch_then(promise_index: u64, account_id_len: u64, account_id_ptr: u64) -> u64;
+    // Available from protocol version 67, see `env::promise_yield_create`.
+    #[cfg(feature = "unstable")]
+    pub fn promise_yield_create(
+        function_name_len: u64,
+        function_name_ptr: u64,
+        arguments_len: u64,
+        arguments_ptr: u64,
+        gas: u64,
+        gas_weight: u64,
+        register_id: u64,
+    ) -> u64;
+    #[cfg(feature = "unstable")]
+    pub fn promise_yield_resume(
+        data_id_len: u64,
+        data_id_ptr: u64,
+        payload_len: u64,
+        payload_ptr: u64,
+    ) -> u32;
     // #######################
     // # Promise API actions #
     // #######################
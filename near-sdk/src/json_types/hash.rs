@@ -21,6 +21,23 @@ impl From<CryptoHash> for Base58CryptoHash {
     }
 }
 
+impl AsRef<[u8]> for Base58CryptoHash {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl TryFrom<Vec<u8>> for Base58CryptoHash {
+    type Error = ParseCryptoHashError;
+
+    fn try_from(data: Vec<u8>) -> Result<Self, Self::Error> {
+        let crypto_hash = CryptoHash::try_from(data.as_slice()).map_err(|_| {
+            ParseCryptoHashError { kind: ParseCryptoHashErrorKind::InvalidLength(data.len()) }
+        })?;
+        Ok(Self(crypto_hash))
+    }
+}
+
 impl ser::Serialize for Base58CryptoHash {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -106,3 +123,43 @@ impl From<B58Error> for ParseCryptoHashError {
 }
 
 impl std::error::Error for ParseCryptoHashError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash() -> Base58CryptoHash {
+        let mut bytes = [0u8; 32];
+        for (i, byte) in bytes.iter_mut().enumerate() {
+            *byte = i as u8;
+        }
+        Base58CryptoHash::from(bytes)
+    }
+
+    #[test]
+    fn test_base58_round_trip() {
+        let json = serde_json::to_string(&hash()).unwrap();
+        assert_eq!(json, "\"1thX6LZfHDZZKUs92febYZhYRcXddmzfzF2NvTkPNE\"");
+        let decoded: Base58CryptoHash = serde_json::from_str(&json).unwrap();
+        assert_eq!(decoded, hash());
+        assert_eq!(decoded.as_ref(), &CryptoHash::from(hash())[..]);
+    }
+
+    #[test]
+    fn test_wrong_length() {
+        let err = Base58CryptoHash::try_from(vec![1u8; 31]).unwrap_err();
+        assert_eq!(err.to_string(), "invalid length of the crypto hash, expected 32 got 31");
+        assert!(Base58CryptoHash::try_from(vec![1u8; 33]).is_err());
+        assert_eq!(Base58CryptoHash::try_from(hash().as_ref().to_vec()).unwrap(), hash());
+
+        let short = bs58::encode(&[1u8; 31]).into_string();
+        assert!(serde_json::from_str::<Base58CryptoHash>(&format!("\"{}\"", short)).is_err());
+    }
+
+    #[test]
+    fn test_borsh_round_trip() {
+        let encoded = hash().try_to_vec().unwrap();
+        assert_eq!(encoded, CryptoHash::from(hash()).to_vec());
+        assert_eq!(Base58CryptoHash::try_from_slice(&encoded).unwrap(), hash());
+    }
+}
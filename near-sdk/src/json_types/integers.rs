@@ -117,4 +117,31 @@ mod tests {
         test_serde!(I64, i64, i64::max_value());
         test_serde!(I64, i64, i64::min_value());
     }
+
+    #[test]
+    fn test_json_format() {
+        assert_eq!(
+            serde_json::to_string(&U128(u128::max_value())).unwrap(),
+            "\"340282366920938463463374607431768211455\""
+        );
+        assert_eq!(serde_json::to_string(&U64(10)).unwrap(), "\"10\"");
+        assert_eq!(serde_json::to_string(&I64(-10)).unwrap(), "\"-10\"");
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        for invalid in &["\"abc\"", "\"\"", "\"1.5\"", "\" 1\"", "\"-1\"", "1", "null"] {
+            assert!(serde_json::from_str::<U128>(invalid).is_err(), "{} was accepted", invalid);
+        }
+        assert!(serde_json::from_str::<U64>("\"18446744073709551616\"").is_err());
+    }
+
+    #[test]
+    fn test_borsh() {
+        let value = U128(u128::max_value());
+        let bytes = value.try_to_vec().unwrap();
+        assert_eq!(bytes, u128::max_value().try_to_vec().unwrap());
+        assert_eq!(U128::try_from_slice(&bytes).unwrap(), value);
+        assert_eq!(U64(5).try_to_vec().unwrap().len(), 8);
+    }
 }
@@ -94,4 +94,23 @@ mod tests {
         let a_deser: Base64VecU8 = serde_json::from_str(&a_str).unwrap();
         assert_eq!(a_deser.0, a);
     }
+
+    #[test]
+    fn test_non_utf8() {
+        assert!(String::from_utf8(vec![0xc3, 0x28, 0xff, 0xfe]).is_err());
+        test_serde!(vec![0xc3, 0x28, 0xff, 0xfe]);
+    }
+
+    #[test]
+    fn test_invalid_base64() {
+        for invalid in &["\"not base64\"", "\"ZHk=fFAA\"", "\"Z\"", "[1, 2]", "null"] {
+            assert!(
+                serde_json::from_str::<Base64VecU8>(invalid).is_err(),
+                "{} was accepted",
+                invalid
+            );
+        }
+        let err = serde_json::from_str::<Base64VecU8>("\"a!b=\"").unwrap_err();
+        assert!(err.to_string().starts_with("Invalid byte 33, offset 1."), "{}", err);
+    }
 }
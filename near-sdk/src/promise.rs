@@ -222,11 +222,15 @@ impl Promise {
     }
 
     fn add_action(self, action: PromiseAction) -> Self {
+        self.add_action_ref(action);
+        self
+    }
+
+    fn add_action_ref(&self, action: PromiseAction) {
         match &self.subtype {
             PromiseSubtype::Single(x) => x.actions.borrow_mut().push(action),
             PromiseSubtype::Joint(_) => panic!("Cannot add action to a joint promise."),
         }
-        self
     }
 
     /// Create account on which this promise acts.
@@ -255,6 +259,53 @@ impl Promise {
         self.add_action(PromiseAction::Transfer { amount })
     }
 
+    /// Schedules a transfer of the given amount to every recipient. Transfers to the same account
+    /// are batched into one promise, so one promise is returned per distinct recipient, in the
+    /// order the recipients first appear.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the total amount exceeds the balance of the current account, before any
+    /// transfer is scheduled.
+    ///
+    /// # Examples
+    /// ```
+    /// # near_sdk::test_utils::test_env::setup();
+    /// use near_sdk::Promise;
+    ///
+    /// let payouts = vec![("alice.near".parse().unwrap(), 10), ("bob.near".parse().unwrap(), 20)];
+    /// let promises = Promise::transfer_many(&payouts);
+    /// assert_eq!(promises.len(), 2);
+    /// ```
+    pub fn transfer_many(recipients: &[(AccountId, Balance)]) -> Vec<Promise> {
+        let total = recipients
+            .iter()
+            .try_fold(0 as Balance, |total, (_, amount)| total.checked_add(*amount));
+        let balance = crate::env::account_balance();
+        match total {
+            Some(total) if total <= balance => {}
+            Some(total) => crate::env::panic(
+                format!(
+                    "The total transfer amount {} exceeds the account balance {}",
+                    total, balance
+                )
+                .as_bytes(),
+            ),
+            None => crate::env::panic(b"The total transfer amount overflows"),
+        }
+
+        let mut promises: Vec<Promise> = vec![];
+        let mut promise_indices: HashMap<&AccountId, usize> = HashMap::new();
+        for (account_id, amount) in recipients {
+            let index = *promise_indices.entry(account_id).or_insert_with(|| {
+                promises.push(Promise::new(account_id.clone()));
+                promises.len() - 1
+            });
+            promises[index].add_action_ref(PromiseAction::Transfer { amount: *amount });
+        }
+        promises
+    }
+
     /// Stake the account for the given amount of tokens using the given public key.
     pub fn stake(self, amount: Balance, public_key: PublicKey) -> Self {
         self.add_action(PromiseAction::Stake { amount, public_key })
@@ -429,6 +480,26 @@ impl borsh::BorshSerialize for Promise {
     }
 }
 
+/// The return type of a method that either returns a value right away or schedules a promise whose
+/// result becomes the result of the method.
+///
+/// When returned from a `#[near_bindgen]` method, `Value` is serialized with the result serializer
+/// of the method, while `Promise` is not serialized and is instead returned with
+/// [`env::promise_return`](crate::env::promise_return), so the caller receives the result of the
+/// promise once it resolves.
+///
+/// # Example
+/// ```ignore
+/// #[near_bindgen]
+/// impl Contract {
+///     pub fn get_or_fetch(&self, key: String) -> PromiseOrValue<String> {
+///         match self.cache.get(&key) {
+///             Some(value) => PromiseOrValue::Value(value),
+///             None => ext_storage::fetch(key, &self.storage_account, 0, env::prepaid_gas() / 2).into(),
+///         }
+///     }
+/// }
+/// ```
 #[derive(serde::Serialize)]
 #[serde(untagged)]
 pub enum PromiseOrValue<T> {
@@ -467,3 +538,172 @@ impl<T: borsh::BorshSerialize> borsh::BorshSerialize for PromiseOrValue<T> {
         }
     }
 }
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::with_mocked_blockchain;
+    use crate::test_utils::test_env;
+    use crate::ReturnData;
+
+    #[test]
+    fn test_value_serialization() {
+        test_env::setup();
+        let value: PromiseOrValue<Vec<u8>> = PromiseOrValue::Value(vec![1, 2]);
+        assert_eq!(serde_json::to_string(&value).unwrap(), "[1,2]");
+        assert_eq!(borsh::BorshSerialize::try_to_vec(&value).unwrap(), vec![2, 0, 0, 0, 1, 2]);
+        assert!(with_mocked_blockchain(|b| b.created_receipts().is_empty()));
+    }
+
+    #[test]
+    fn test_promise_is_returned() {
+        test_env::setup();
+        let promise: PromiseOrValue<u64> =
+            Promise::new("bob.near".parse().unwrap()).transfer(1).into();
+        assert_eq!(serde_json::to_string(&promise).unwrap(), "null");
+        drop(promise);
+        assert_eq!(with_mocked_blockchain(|b| b.created_receipts().len()), 1);
+        assert!(matches!(
+            with_mocked_blockchain(|b| b.outcome().return_data),
+            ReturnData::ReceiptIndex(_)
+        ));
+    }
+
+    #[test]
+    fn test_as_return() {
+        test_env::setup();
+        Promise::new("bob.near".parse().unwrap()).transfer(1).as_return();
+        assert!(matches!(
+            with_mocked_blockchain(|b| b.outcome().return_data),
+            ReturnData::ReceiptIndex(0)
+        ));
+    }
+
+    #[test]
+    fn test_returned_promise_replaces_value() {
+        test_env::setup();
+        // The steps of the `#[near_bindgen]` wrapper of a method that returns a `Promise`: the
+        // serialized result is returned first, then the promise is returned when it's dropped at
+        // the end of the wrapper, which replaces the value.
+        let result = Promise::new("bob.near".parse().unwrap()).transfer(1);
+        let value = serde_json::to_vec(&result).unwrap();
+        crate::env::value_return(&value);
+        assert!(matches!(
+            with_mocked_blockchain(|b| b.outcome().return_data),
+            ReturnData::Value(_)
+        ));
+        drop(result);
+        assert!(matches!(
+            with_mocked_blockchain(|b| b.outcome().return_data),
+            ReturnData::ReceiptIndex(0)
+        ));
+    }
+
+    #[test]
+    fn test_then_and() {
+        test_env::setup();
+        let a = Promise::new("a.near".parse().unwrap()).transfer(1);
+        let b = Promise::new("b.near".parse().unwrap()).transfer(1);
+        let callback = Promise::new(crate::env::current_account_id()).function_call(
+            b"on_done".to_vec(),
+            vec![],
+            0,
+            Gas(5_000_000_000_000),
+        );
+        drop(a.and(b).then(callback));
+        assert_eq!(with_mocked_blockchain(|b| b.created_receipts().len()), 3);
+    }
+
+    #[test]
+    fn test_transfer_many() {
+        use crate::test_utils::{get_transfers, VMContextBuilder};
+        use crate::testing_env;
+
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        let near = 10u128.pow(24);
+        testing_env!(VMContextBuilder::new().account_balance(100 * near).build());
+
+        let promises = Promise::transfer_many(&[
+            (alice.clone(), 10 * near),
+            (bob.clone(), 20 * near),
+            (alice.clone(), 70 * near),
+        ]);
+        assert_eq!(promises.len(), 2);
+        drop(promises);
+
+        assert_eq!(with_mocked_blockchain(|b| b.created_receipts().len()), 2);
+        assert_eq!(
+            get_transfers(),
+            vec![(alice.clone(), 10 * near), (alice, 70 * near), (bob, 20 * near)]
+        );
+        assert!(Promise::transfer_many(&[]).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "The total transfer amount 101 exceeds the account balance 100")]
+    fn test_transfer_many_over_budget() {
+        use crate::test_utils::VMContextBuilder;
+        use crate::testing_env;
+
+        testing_env!(VMContextBuilder::new().account_balance(100).build());
+        Promise::transfer_many(&[
+            ("alice.near".parse().unwrap(), 50),
+            ("bob.near".parse().unwrap(), 51),
+        ]);
+    }
+
+    #[test]
+    #[should_panic(expected = "The total transfer amount overflows")]
+    fn test_transfer_many_overflow() {
+        test_env::setup();
+        Promise::transfer_many(&[
+            ("alice.near".parse().unwrap(), Balance::MAX),
+            ("bob.near".parse().unwrap(), 1),
+        ]);
+    }
+
+    #[test]
+    fn test_batch_actions_in_order() {
+        test_env::setup();
+        let public_key: PublicKey =
+            "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp".parse().unwrap();
+        drop(
+            Promise::new("sub.alice.near".parse().unwrap())
+                .create_account()
+                .transfer(100)
+                .add_full_access_key(public_key)
+                .deploy_contract(vec![1, 2, 3])
+                .function_call(b"new".to_vec(), b"{}".to_vec(), 0, Gas(5_000_000_000_000)),
+        );
+
+        let receipts = crate::test_utils::get_created_receipts();
+        assert_eq!(receipts.len(), 1);
+        // `to_value` does not support the `u128` deposit, so go through the JSON string.
+        let receipt: serde_json::Value =
+            serde_json::from_str(&serde_json::to_string(&receipts[0]).unwrap()).unwrap();
+        assert_eq!(receipt["receiver_id"], "sub.alice.near");
+        let actions: Vec<String> = receipt["actions"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|action| match action {
+                serde_json::Value::String(name) => name.clone(),
+                action => action.as_object().unwrap().keys().next().unwrap().clone(),
+            })
+            .collect();
+        assert_eq!(
+            actions,
+            vec![
+                "CreateAccount",
+                "Transfer",
+                "AddKeyWithFullAccess",
+                "DeployContract",
+                "FunctionCall"
+            ]
+        );
+        assert_eq!(receipt["actions"][1]["Transfer"]["deposit"], 100);
+        assert_eq!(receipt["actions"][4]["FunctionCall"]["method_name"], "new");
+    }
+}
@@ -181,4 +181,33 @@ mod tests {
         // be checked for equality.
         assert_eq!(lazy_loaded, b);
     }
+
+    #[test]
+    pub fn test_lazy_writes_only_when_modified() {
+        test_env::setup();
+        env::storage_write(b"a", &8u32.try_to_vec().unwrap());
+        // A cell for the stored value that is not loaded yet, it's serialized as its key.
+        let serialized = b"a".to_vec().try_to_vec().unwrap();
+        let load = || Lazy::<u32>::try_from_slice(&serialized).unwrap();
+
+        // Remove the stored bytes once the value is cached, so the key only reappears if the
+        // value is written back.
+        let mut a = load();
+        assert_eq!(a.get(), &8);
+        env::storage_remove(b"a");
+        a.flush();
+        drop(a);
+        assert!(!env::storage_has_key(b"a"));
+
+        env::storage_write(b"a", &8u32.try_to_vec().unwrap());
+        let mut a = load();
+        *a.get_mut() += 1;
+        a.flush();
+        assert_eq!(u32::try_from_slice(&env::storage_read(b"a").unwrap()).unwrap(), 9);
+        // The value was written once, neither another flush nor the drop write it again.
+        env::storage_remove(b"a");
+        a.flush();
+        drop(a);
+        assert!(!env::storage_has_key(b"a"));
+    }
 }
@@ -114,6 +114,11 @@ impl VMContextBuilder {
         self
     }
 
+    pub fn input(&mut self, input: Vec<u8>) -> &mut Self {
+        self.context.input = input;
+        self
+    }
+
     pub fn is_view(&mut self, is_view: bool) -> &mut Self {
         self.context.is_view = is_view;
         self
@@ -124,6 +129,18 @@ impl VMContextBuilder {
     }
 }
 
+impl From<VMContextBuilder> for VMContext {
+    fn from(builder: VMContextBuilder) -> Self {
+        builder.context
+    }
+}
+
+impl From<&mut VMContextBuilder> for VMContext {
+    fn from(builder: &mut VMContextBuilder) -> Self {
+        builder.build()
+    }
+}
+
 // TODO: This probably shouldn't be necessary with the `testing_env` macro.
 /// Initializes the [`MockedBlockchain`] with a single promise result during execution.
 pub fn testing_env_with_promise_results(context: VMContext, promise_result: PromiseResult) {
@@ -5,6 +5,8 @@ mod context;
 pub use context::{accounts, testing_env_with_promise_results, VMContextBuilder};
 use near_vm_logic::mocks::mock_external::Receipt;
 
+use crate::{AccountId, Balance, PromiseResult};
+
 /// Initializes a testing environment to mock interactions which would otherwise go through a
 /// validator node. This macro will initialize or overwrite the [`MockedBlockchain`]
 /// instance for interactions from a smart contract.
@@ -12,7 +14,8 @@ use near_vm_logic::mocks::mock_external::Receipt;
 /// There are five parameters that can be accepted to configure the interface with a
 /// [`MockedBlockchain`], in this order:
 /// - `context`: [`VMContext`] which contains some core information about
-/// the blockchain and message data which can be used from the smart contract.
+/// the blockchain and message data which can be used from the smart contract. A
+/// [`VMContextBuilder`] can be passed directly instead of calling `build()` on it.
 /// - `config` (optional): [`VMConfig`] which contains some additional information
 /// about the VM to configure parameters not directly related to the transaction being executed.
 /// - `fee_config`(optional): [`RuntimeFeesConfig`] which configures the
@@ -39,6 +42,9 @@ use near_vm_logic::mocks::mock_external::Receipt;
 /// // Build with just the base context
 /// testing_env!(context.clone());
 ///
+/// // Or pass the builder directly
+/// testing_env!(VMContextBuilder::new().predecessor_account_id(accounts(1)));
+///
 /// // Or include arguments up to the five optional
 /// testing_env!(
 ///     context,
@@ -52,6 +58,7 @@ use near_vm_logic::mocks::mock_external::Receipt;
 ///
 /// [`MockedBlockchain`]: crate::mock::MockedBlockchain
 /// [`VMContext`]: crate::VMContext
+/// [`VMContextBuilder`]: crate::test_utils::VMContextBuilder
 /// [`VMConfig`]: crate::VMConfig
 /// [`RuntimeFeesConfig`]: crate::RuntimeFeesConfig
 /// [`AccountId`]: crate::AccountId
@@ -62,7 +69,7 @@ use near_vm_logic::mocks::mock_external::Receipt;
 macro_rules! testing_env {
     ($context:expr, $config:expr, $fee_config:expr, $validators:expr, $promise_results:expr $(,)?) => {
         $crate::env::set_blockchain_interface($crate::MockedBlockchain::new(
-            $context,
+            $context.into(),
             $config,
             $fee_config,
             $promise_results,
@@ -89,12 +96,138 @@ pub fn get_logs() -> Vec<String> {
     crate::env::BLOCKCHAIN_INTERFACE.with(|b| b.borrow().logs())
 }
 
+/// Asserts that the given line was logged through the [`MockedBlockchain`]. The line has to match
+/// one of the captured logs exactly. On failure, the expected line is printed alongside all of
+/// the captured logs.
+///
+/// # Example use
+///
+/// ```
+/// use near_sdk::{assert_logged, log};
+///
+/// # fn main() {
+/// log!("hello {}", "world");
+/// assert_logged!("hello world");
+/// # }
+/// ```
+///
+/// [`MockedBlockchain`]: crate::mock::MockedBlockchain
+#[macro_export]
+macro_rules! assert_logged {
+    ($expected:expr $(,)?) => {{
+        let expected = &$expected;
+        let expected: &str = expected.as_ref();
+        let logs = $crate::test_utils::get_logs();
+        if !logs.iter().any(|log| log == expected) {
+            panic!(
+                "assertion failed: line was not logged\n expected: `{:?}`,\n     logs: `{:?}`",
+                expected, logs
+            );
+        }
+    }};
+}
+
+/// Asserts that at least one of the logs captured by the [`MockedBlockchain`] contains the given
+/// substring. On failure, the substring is printed alongside all of the captured logs.
+///
+/// # Example use
+///
+/// ```
+/// use near_sdk::{assert_logged_contains, log};
+///
+/// # fn main() {
+/// log!("Transferred {} tokens", 10);
+/// assert_logged_contains!("10 tokens");
+/// # }
+/// ```
+///
+/// [`MockedBlockchain`]: crate::mock::MockedBlockchain
+#[macro_export]
+macro_rules! assert_logged_contains {
+    ($substring:expr $(,)?) => {{
+        let substring = &$substring;
+        let substring: &str = substring.as_ref();
+        let logs = $crate::test_utils::get_logs();
+        if !logs.iter().any(|log| log.contains(substring)) {
+            panic!(
+                "assertion failed: no log contains substring\nsubstring: `{:?}`,\n     logs: `{:?}`",
+                substring, logs
+            );
+        }
+    }};
+}
+
 /// Accessing receipts created by the contract. Only available in unit tests.
 #[allow(dead_code)]
 pub fn get_created_receipts() -> Vec<Receipt> {
     crate::env::BLOCKCHAIN_INTERFACE.with(|b| b.borrow().created_receipts().clone())
 }
 
+/// Returns the receiver and the amount of every transfer scheduled by the contract, in the order
+/// they were scheduled. Only available in unit tests.
+pub fn get_transfers() -> Vec<(AccountId, Balance)> {
+    use serde::de::IgnoredAny;
+    use serde::Deserialize;
+
+    // The fields of the mocked receipts are private, so they are read through their serialized
+    // form. Only the transfers are of interest here, the other actions are skipped.
+    #[derive(Deserialize)]
+    struct MockReceipt {
+        receiver_id: String,
+        actions: Vec<MockAction>,
+    }
+
+    #[derive(Deserialize)]
+    enum MockAction {
+        Transfer { deposit: Balance },
+        CreateAccount,
+        DeployContract(IgnoredAny),
+        FunctionCall(IgnoredAny),
+        Stake(IgnoredAny),
+        AddKeyWithFullAccess(IgnoredAny),
+        AddKeyWithFunctionCall(IgnoredAny),
+        DeleteKey(IgnoredAny),
+        DeleteAccount(IgnoredAny),
+    }
+
+    let json =
+        serde_json::to_string(&get_created_receipts()).expect("Failed to serialize receipts");
+    let receipts: Vec<MockReceipt> =
+        serde_json::from_str(&json).expect("Failed to deserialize receipts");
+    receipts
+        .into_iter()
+        .flat_map(|receipt| {
+            let receiver_id = AccountId::new_unchecked(receipt.receiver_id);
+            receipt.actions.into_iter().filter_map(move |action| match action {
+                MockAction::Transfer { deposit } => Some((receiver_id.clone(), deposit)),
+                _ => None,
+            })
+        })
+        .collect()
+}
+
+/// Returns the method names and the sizes of the values they returned, recorded for the
+/// `#[near_bindgen]` methods with the `debug-return-size` feature. Only available in unit tests.
+pub fn get_return_sizes() -> Vec<(String, usize)> {
+    crate::mock::with_mocked_blockchain(|b| b.return_sizes())
+}
+
+/// Returns the result that the promise created with [`account_exists_probe`] would have if only
+/// `existing_accounts` existed. Pass it in the promise results of `testing_env!` to test the
+/// callback of the probe.
+///
+/// [`account_exists_probe`]: crate::account_exists_probe
+pub fn account_probe_result(
+    existing_accounts: &[AccountId],
+    account_id: &AccountId,
+) -> PromiseResult {
+    if existing_accounts.contains(account_id) {
+        PromiseResult::Successful(vec![])
+    } else {
+        PromiseResult::Failed
+    }
+}
+
 /// Objects stored on the trie directly should have identifiers. If identifier is not provided
 /// explicitly than `Default` trait would use this index to generate an id.
 #[allow(dead_code)]
@@ -108,3 +241,47 @@ pub(crate) fn next_trie_id() -> Vec<u8> {
         id.to_le_bytes().to_vec()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::env;
+    use crate::test_utils::test_env;
+
+    #[test]
+    fn test_assert_logged() {
+        test_env::setup();
+        env::log_str("hello");
+        env::log_str("hello world");
+        assert_logged!("hello");
+        assert_logged!("hello world");
+        assert_logged!(String::from("hello world"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: line was not logged\n expected: `\"hello\"`,\n     logs: `[\"hello world\"]`"
+    )]
+    fn test_assert_logged_fail() {
+        test_env::setup();
+        env::log_str("hello world");
+        assert_logged!("hello");
+    }
+
+    #[test]
+    fn test_assert_logged_contains() {
+        test_env::setup();
+        env::log_str("Transferred 10 tokens to bob.near");
+        assert_logged_contains!("10 tokens");
+        assert_logged_contains!("bob.near");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "assertion failed: no log contains substring\nsubstring: `\"alice\"`,\n     logs: `[\"bob\"]`"
+    )]
+    fn test_assert_logged_contains_fail() {
+        test_env::setup();
+        env::log_str("bob");
+        assert_logged_contains!("alice");
+    }
+}
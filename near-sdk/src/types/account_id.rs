@@ -4,14 +4,17 @@ use std::convert::TryFrom;
 use std::fmt;
 use std::io::{self, ErrorKind};
 
-use crate::env::is_valid_account_id;
+/// The minimum length of a valid account ID.
+const MIN_ACCOUNT_ID_LEN: usize = 2;
+/// The maximum length of a valid account ID.
+const MAX_ACCOUNT_ID_LEN: usize = 64;
 
 /// Account identifier. This is the human readable utf8 string which is used internally to index
 /// accounts on the network and their respective state.
 ///
 /// Because these IDs have to be validated, they have to be converted from a string
-/// with [`FromStr`] or [`TryFrom`] a compatible type. To skip validation on initialization,
-/// [`AccountId::new_unchecked`] can be used.
+/// with [`FromStr`] or [`TryFrom`] a compatible type. The rules of a valid account ID are
+/// described in [`AccountId::validate`].
 ///
 /// # Examples
 /// ```
@@ -27,12 +30,12 @@ use crate::env::is_valid_account_id;
 /// // From string with validation
 /// let alice = AccountId::try_from(alice_string.clone()).unwrap();
 /// let alice: AccountId = alice_string.try_into().unwrap();
-///
-/// // Initialize without validating
-/// let alice_unchecked = AccountId::new_unchecked("alice".to_string());
-/// assert_eq!(alice, alice_unchecked);
 /// ```
 ///
+/// Cloning an `AccountId` allocates a copy of the string. Functions that only read the ID can take
+/// an [`AccountIdRef`] instead, which [`as_account_id_ref`](Self::as_account_id_ref) returns
+/// without allocating.
+///
 /// [`FromStr`]: std::str::FromStr
 #[derive(
     Debug, Clone, PartialEq, PartialOrd, Ord, Eq, BorshSerialize, Serialize, Hash, BorshSchema,
@@ -48,14 +51,146 @@ impl AccountId {
     pub fn as_str(&self) -> &str {
         self.0.as_str()
     }
+    /// Returns a borrowed view of the account ID, which is `Copy` and doesn't allocate.
+    pub fn as_account_id_ref(&self) -> AccountIdRef<'_> {
+        AccountIdRef(self.0.as_str())
+    }
     /// Constructs new AccountId from `String` without checking validity.
-    /// Creating an invalid account id will result in a runtime error when being used.
     ///
-    /// For more information, read: https://docs.near.org/docs/concepts/account#account-id-rules
+    /// This is only checked in debug builds, so in release builds nothing prevents creating an
+    /// invalid account ID with this function. Invalid account IDs will be rejected by the runtime
+    /// when used, for example as the receiver of a promise, which fails the whole execution.
+    /// Prefer [`FromStr`](std::str::FromStr) or [`TryFrom`] which validate the ID.
+    #[doc(hidden)]
     pub fn new_unchecked(id: String) -> Self {
-        debug_assert!(is_valid_account_id(id.as_bytes()));
+        debug_assert!(validate_account_id(id.as_bytes()).is_ok());
         Self(id)
     }
+
+    /// Validates the given string as an account ID.
+    ///
+    /// A valid account ID:
+    /// - is between 2 and 64 characters long;
+    /// - consists of lowercase alphanumeric characters separated by `.`, `-` or `_`;
+    /// - does not start or end with a separator and does not contain two consecutive separators.
+    ///
+    /// For more information, read: https://docs.near.org/docs/concepts/account#account-id-rules
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::{AccountId, ParseAccountIdErrorKind};
+    ///
+    /// assert!(AccountId::validate("alice.near").is_ok());
+    /// assert_eq!(AccountId::validate("a").unwrap_err().kind(), &ParseAccountIdErrorKind::TooShort);
+    /// ```
+    pub fn validate(id: &str) -> Result<(), ParseAccountIdError> {
+        validate_account_id(id.as_bytes())
+    }
+
+    /// Returns `true` if the given string is a valid account ID, see [`validate`](Self::validate)
+    /// for the rules. The check doesn't allocate, so it is cheap enough to filter a batch of
+    /// account IDs before acting on them.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::AccountId;
+    ///
+    /// let batch = ["alice.near", "Bob.near", "carol.near"];
+    /// let valid: Vec<&str> = batch.iter().copied().filter(|id| AccountId::is_valid(id)).collect();
+    /// assert_eq!(valid, ["alice.near", "carol.near"]);
+    /// ```
+    pub fn is_valid(id: &str) -> bool {
+        validate_account_id(id.as_bytes()).is_ok()
+    }
+
+    /// Returns the account ID of the direct parent of this account, which is everything after
+    /// the first `.`, or `None` if this is a top-level account.
+    ///
+    /// Implicit account IDs (64 character hex encoded public keys) contain no `.` and so never
+    /// have a parent. `None` is also returned if the remainder is not a valid account ID on its
+    /// own, such as `"a"` in `"alice.a"`.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::AccountId;
+    ///
+    /// let account: AccountId = "foo.alice.near".parse().unwrap();
+    /// assert_eq!(account.parent_account_id(), Some("alice.near".parse().unwrap()));
+    ///
+    /// let top_level: AccountId = "near".parse().unwrap();
+    /// assert_eq!(top_level.parent_account_id(), None);
+    /// ```
+    pub fn parent_account_id(&self) -> Option<AccountId> {
+        self.parent_str().and_then(|parent| parent.parse().ok())
+    }
+
+    /// Returns `true` if this account is a direct sub-account of `parent`, for example
+    /// `foo.alice.near` is a sub-account of `alice.near` but not of `near`.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::AccountId;
+    ///
+    /// let account: AccountId = "foo.alice.near".parse().unwrap();
+    /// assert!(account.is_sub_account_of(&"alice.near".parse().unwrap()));
+    /// assert!(!account.is_sub_account_of(&"near".parse().unwrap()));
+    /// ```
+    pub fn is_sub_account_of(&self, parent: &AccountId) -> bool {
+        self.parent_str() == Some(parent.as_str())
+    }
+
+    /// Returns `true` if this is an implicit account, whose ID is the 64 character lowercase hex
+    /// encoding of an ED25519 public key.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::AccountId;
+    ///
+    /// let implicit: AccountId =
+    ///     "e09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7c9d1e".parse().unwrap();
+    /// assert!(implicit.is_implicit());
+    /// assert!(!"alice.near".parse::<AccountId>().unwrap().is_implicit());
+    /// ```
+    pub fn is_implicit(&self) -> bool {
+        self.as_account_id_ref().is_implicit()
+    }
+
+    /// Returns `true` if this is a top-level account like `near`, which has no `.` and is not an
+    /// implicit account. Only the registrar can create top-level accounts.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::AccountId;
+    ///
+    /// assert!("near".parse::<AccountId>().unwrap().is_top_level());
+    /// assert!(!"alice.near".parse::<AccountId>().unwrap().is_top_level());
+    /// ```
+    pub fn is_top_level(&self) -> bool {
+        self.as_account_id_ref().is_top_level()
+    }
+
+    /// Returns the UTF-8 bytes of the account ID to use as a storage key, which is shorter than the
+    /// Borsh serialization and readable when inspecting the trie.
+    ///
+    /// This differs from the Borsh layout that collections use for their keys, which has a 4 byte
+    /// length before the bytes, so don't mix the two in the same collection. The key also isn't
+    /// prefix-free: `alice` is a prefix of `alice.near`, so when the key is used as the prefix of
+    /// a nested collection, end it with a separator that can't occur in account IDs, like `b'/'`.
+    ///
+    /// # Examples
+    /// ```
+    /// use near_sdk::AccountId;
+    ///
+    /// let alice: AccountId = "alice.near".parse().unwrap();
+    /// assert_eq!(alice.to_storage_key(), b"alice.near".to_vec());
+    /// ```
+    pub fn to_storage_key(&self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+
+    fn parent_str(&self) -> Option<&str> {
+        self.0.find('.').map(|idx| &self.0[idx + 1..])
+    }
 }
 
 impl fmt::Display for AccountId {
@@ -93,11 +228,133 @@ impl BorshDeserialize for AccountId {
     }
 }
 
-fn validate_account_id(id: &str) -> Result<(), ParseAccountIdError> {
-    if is_valid_account_id(id.as_bytes()) {
-        Ok(())
-    } else {
-        Err(ParseAccountIdError {})
+pub(crate) fn validate_account_id(id: &[u8]) -> Result<(), ParseAccountIdError> {
+    if id.len() < MIN_ACCOUNT_ID_LEN {
+        return Err(ParseAccountIdError { kind: ParseAccountIdErrorKind::TooShort });
+    }
+    if id.len() > MAX_ACCOUNT_ID_LEN {
+        return Err(ParseAccountIdError { kind: ParseAccountIdErrorKind::TooLong });
+    }
+
+    // NOTE: We don't want to use Regex here, because it requires extra time to compile it.
+    // The valid account ID regex is /^(([a-z\d]+[-_])*[a-z\d]+\.)*([a-z\d]+[-_])*[a-z\d]+$/
+    // Instead the implementation is based on the previous character checks.
+
+    // We can safely assume that last char was a separator.
+    let mut last_char_is_separator = true;
+
+    for c in id {
+        let current_char_is_separator = match *c {
+            b'a'..=b'z' | b'0'..=b'9' => false,
+            b'-' | b'_' | b'.' => true,
+            _ => return Err(ParseAccountIdError { kind: ParseAccountIdErrorKind::InvalidChar }),
+        };
+        if current_char_is_separator && last_char_is_separator {
+            return Err(ParseAccountIdError { kind: ParseAccountIdErrorKind::RedundantSeparator });
+        }
+        last_char_is_separator = current_char_is_separator;
+    }
+    // The account can't end as separator.
+    if last_char_is_separator {
+        return Err(ParseAccountIdError { kind: ParseAccountIdErrorKind::RedundantSeparator });
+    }
+    Ok(())
+}
+
+/// A borrowed, validated account ID. It serializes with serde and Borsh exactly like
+/// [`AccountId`], so functions and event structs that only read the ID can take it instead of an
+/// owned `AccountId` and avoid cloning.
+///
+/// # Examples
+/// ```
+/// use near_sdk::{AccountId, AccountIdRef};
+///
+/// fn greet(account_id: AccountIdRef) -> String {
+///     format!("Hello, {}", account_id)
+/// }
+///
+/// let alice: AccountId = "alice.near".parse().unwrap();
+/// assert_eq!(greet(alice.as_account_id_ref()), "Hello, alice.near");
+/// assert_eq!(greet(AccountIdRef::from_static("bob.near")), "Hello, bob.near");
+/// assert!(AccountIdRef::new("Invalid").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Ord, Eq, Serialize, Hash)]
+pub struct AccountIdRef<'a>(&'a str);
+
+impl<'a> AccountIdRef<'a> {
+    /// Validates the string as an account ID, see [`AccountId::validate`].
+    pub fn new(id: &'a str) -> Result<Self, ParseAccountIdError> {
+        AccountId::validate(id)?;
+        Ok(Self(id))
+    }
+
+    /// Returns the account ID as a string slice.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Returns the bytes of the account ID.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.0.as_bytes()
+    }
+
+    /// See [`AccountId::is_implicit`].
+    pub fn is_implicit(&self) -> bool {
+        self.0.len() == 64 && self.0.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+    }
+
+    /// See [`AccountId::is_top_level`].
+    pub fn is_top_level(&self) -> bool {
+        !self.0.contains('.') && !self.is_implicit()
+    }
+
+    /// Copies the account ID into an owned [`AccountId`].
+    pub fn to_account_id(&self) -> AccountId {
+        AccountId(self.0.to_string())
+    }
+}
+
+impl AccountIdRef<'static> {
+    /// Creates the view of an account ID known at compile time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `id` is not a valid account ID.
+    pub fn from_static(id: &'static str) -> Self {
+        match Self::new(id) {
+            Ok(id) => id,
+            Err(err) => panic!("{}: {}", id, err),
+        }
+    }
+}
+
+impl fmt::Display for AccountIdRef<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.0, f)
+    }
+}
+
+impl AsRef<str> for AccountIdRef<'_> {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl BorshSerialize for AccountIdRef<'_> {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        BorshSerialize::serialize(self.0, writer)
+    }
+}
+
+impl<'a> From<&'a AccountId> for AccountIdRef<'a> {
+    fn from(id: &'a AccountId) -> Self {
+        id.as_account_id_ref()
+    }
+}
+
+impl PartialEq<AccountId> for AccountIdRef<'_> {
+    fn eq(&self, other: &AccountId) -> bool {
+        self.0 == other.as_str()
     }
 }
 
@@ -105,7 +362,7 @@ impl TryFrom<String> for AccountId {
     type Error = ParseAccountIdError;
 
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        validate_account_id(value.as_str())?;
+        Self::validate(value.as_str())?;
         Ok(Self(value))
     }
 }
@@ -114,18 +371,48 @@ impl std::str::FromStr for AccountId {
     type Err = ParseAccountIdError;
 
     fn from_str(value: &str) -> Result<Self, Self::Err> {
-        validate_account_id(value)?;
+        Self::validate(value)?;
         Ok(Self(value.to_string()))
     }
 }
 
+/// Error returned when a string is not a valid [`AccountId`].
 #[derive(Debug, Clone, PartialEq, Eq)]
 #[non_exhaustive]
-pub struct ParseAccountIdError {}
+pub struct ParseAccountIdError {
+    kind: ParseAccountIdErrorKind,
+}
+
+impl ParseAccountIdError {
+    /// Returns the reason the account ID is invalid.
+    pub fn kind(&self) -> &ParseAccountIdErrorKind {
+        &self.kind
+    }
+}
+
+/// The reason an account ID failed to validate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ParseAccountIdErrorKind {
+    /// The account ID is shorter than 2 characters.
+    TooShort,
+    /// The account ID is longer than 64 characters.
+    TooLong,
+    /// The account ID contains a character other than lowercase alphanumerics and separators.
+    InvalidChar,
+    /// The account ID starts or ends with a separator, or contains two consecutive separators.
+    RedundantSeparator,
+}
 
 impl fmt::Display for ParseAccountIdError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "the account ID is invalid")
+        let reason = match self.kind {
+            ParseAccountIdErrorKind::TooShort => "it is too short",
+            ParseAccountIdErrorKind::TooLong => "it is too long",
+            ParseAccountIdErrorKind::InvalidChar => "it contains an invalid character",
+            ParseAccountIdErrorKind::RedundantSeparator => "it contains a redundant separator",
+        };
+        write!(f, "the account ID is invalid because {}", reason)
     }
 }
 
@@ -157,6 +444,105 @@ mod tests {
         assert_eq!(key.as_ref(), &"alice.near".to_string());
     }
 
+    #[test]
+    fn test_validate() {
+        assert!(AccountId::validate("alice.near").is_ok());
+        assert!(AccountId::validate("aa").is_ok());
+        assert!(AccountId::validate(&"a".repeat(64)).is_ok());
+
+        let kind = |id: &str| *AccountId::validate(id).unwrap_err().kind();
+        assert_eq!(kind(""), ParseAccountIdErrorKind::TooShort);
+        assert_eq!(kind("a"), ParseAccountIdErrorKind::TooShort);
+        assert_eq!(kind(&"a".repeat(65)), ParseAccountIdErrorKind::TooLong);
+        assert_eq!(kind("Alice.near"), ParseAccountIdErrorKind::InvalidChar);
+        assert_eq!(kind("alice@near"), ParseAccountIdErrorKind::InvalidChar);
+        assert_eq!(kind("alice..near"), ParseAccountIdErrorKind::RedundantSeparator);
+        assert_eq!(kind(".near"), ParseAccountIdErrorKind::RedundantSeparator);
+        assert_eq!(kind("near-"), ParseAccountIdErrorKind::RedundantSeparator);
+
+        let err = "a".parse::<AccountId>().unwrap_err();
+        assert_eq!(err.kind(), &ParseAccountIdErrorKind::TooShort);
+        let err = AccountId::try_from("a".repeat(65)).unwrap_err();
+        assert_eq!(err.kind(), &ParseAccountIdErrorKind::TooLong);
+    }
+
+    #[test]
+    fn test_is_valid() {
+        // Implicit account: hex encoded ED25519 public key.
+        let implicit = "98793cd91a3f870fb126f66285808c7e094afcfc4eda8a970f6648cdf0dbd6de";
+        assert_eq!(implicit.len(), 64);
+        assert!(AccountId::is_valid(implicit));
+        assert!(AccountId::is_valid("system"));
+        assert!(AccountId::is_valid("near"));
+        assert!(AccountId::is_valid("sub_1.alice-bob.near"));
+
+        assert!(!AccountId::is_valid("Alice.near"));
+        assert!(!AccountId::is_valid("ALICE"));
+        assert!(!AccountId::is_valid(&implicit.to_uppercase()));
+        assert!(!AccountId::is_valid(&format!("{}0", implicit)));
+        assert!(!AccountId::is_valid("a"));
+        assert!(!AccountId::is_valid("alice.-near"));
+        assert!(!AccountId::is_valid("alice.near."));
+        assert!(!AccountId::is_valid("alice near"));
+    }
+
+    #[test]
+    fn test_parent_account_id() {
+        let id = |s: &str| s.parse::<AccountId>().unwrap();
+
+        assert_eq!(id("a.b.c.near").parent_account_id(), Some(id("b.c.near")));
+        assert_eq!(id("b.c.near").parent_account_id(), Some(id("c.near")));
+        assert_eq!(id("c.near").parent_account_id(), Some(id("near")));
+        assert_eq!(id("near").parent_account_id(), None);
+        // Remainder is too short to be an account ID.
+        assert_eq!(id("alice.a").parent_account_id(), None);
+        // Implicit accounts have no parent.
+        let implicit = id("e09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7c9d1e");
+        assert_eq!(implicit.parent_account_id(), None);
+    }
+
+    #[test]
+    fn test_is_sub_account_of() {
+        let id = |s: &str| s.parse::<AccountId>().unwrap();
+
+        assert!(id("a.b.c.near").is_sub_account_of(&id("b.c.near")));
+        assert!(!id("a.b.c.near").is_sub_account_of(&id("c.near")));
+        assert!(!id("a.b.c.near").is_sub_account_of(&id("near")));
+        assert!(!id("a.b.c.near").is_sub_account_of(&id("a.b.c.near")));
+        assert!(!id("b.c.near").is_sub_account_of(&id("a.b.c.near")));
+        assert!(!id("foo.xc.near").is_sub_account_of(&id("c.near")));
+        assert!(!id("near").is_sub_account_of(&id("near")));
+        let implicit = id("e09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7c9d1e");
+        assert!(!implicit.is_sub_account_of(&id("near")));
+    }
+
+    #[test]
+    fn test_is_implicit_top_level() {
+        let id = |s: &str| s.parse::<AccountId>().unwrap();
+
+        let implicit = id("e09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7c9d1e");
+        assert!(implicit.is_implicit());
+        assert!(!implicit.is_top_level());
+
+        assert!(id("near").is_top_level());
+        assert!(!id("near").is_implicit());
+
+        assert!(!id("alice.near").is_top_level());
+        assert!(!id("alice.near").is_implicit());
+
+        // One character short, or with characters outside of lowercase hex.
+        assert!(
+            !id("e09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7c9d1").is_implicit()
+        );
+        assert!(
+            !id("g09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7c9d1e").is_implicit()
+        );
+        // 64 characters, but not all of them hex.
+        assert!(
+            !id("e09a4b2a5b7d1f3c6e8a0b2c4d6e8f0a1b3c5d7e9f1a3b5c7d9e1f3a5b7.near").is_implicit()
+        );
+    }
+
     #[test]
     fn borsh_serialize_impl() {
         let id = "test.near";
@@ -165,4 +551,41 @@ mod tests {
         // Test to make sure the account ID is serialized as a string through borsh
         assert_eq!(str::try_to_vec(&id).unwrap(), account_id.try_to_vec().unwrap());
     }
+
+    #[test]
+    fn test_to_storage_key() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let bob: AccountId = "bob.near".parse().unwrap();
+        assert_eq!(alice.to_storage_key(), alice.clone().to_storage_key());
+        assert_ne!(alice.to_storage_key(), bob.to_storage_key());
+        assert_eq!(alice.to_storage_key(), b"alice.near");
+        // Not the Borsh layout, which starts with the length.
+        assert_ne!(alice.to_storage_key(), alice.try_to_vec().unwrap());
+    }
+
+    #[test]
+    fn test_account_id_ref() {
+        let alice: AccountId = "alice.near".parse().unwrap();
+        let alice_ref = alice.as_account_id_ref();
+        assert_eq!(alice_ref, alice);
+        assert_eq!(alice_ref.as_str(), "alice.near");
+        assert_eq!(alice_ref.to_account_id(), alice);
+        assert_eq!(AccountIdRef::from(&alice), AccountIdRef::from_static("alice.near"));
+
+        // Serializes the same as the owned account ID.
+        assert_eq!(
+            serde_json::to_string(&alice_ref).unwrap(),
+            serde_json::to_string(&alice).unwrap()
+        );
+        assert_eq!(alice_ref.try_to_vec().unwrap(), alice.try_to_vec().unwrap());
+
+        assert_eq!(AccountIdRef::new("a").unwrap_err().kind(), &ParseAccountIdErrorKind::TooShort);
+        assert!(AccountIdRef::new("alice.near").is_ok());
+    }
+
+    #[test]
+    #[should_panic(expected = "Alice.near: the account ID is invalid")]
+    fn test_account_id_ref_from_static_invalid() {
+        AccountIdRef::from_static("Alice.near");
+    }
 }
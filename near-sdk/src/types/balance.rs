@@ -0,0 +1,116 @@
+use super::Balance;
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+/// Adds two balances, returning `None` instead of overflowing. Wasm contracts are built without
+/// overflow checks in release mode, so `a + b` silently wraps around there and can mint tokens.
+///
+/// ```
+/// use near_sdk::checked_add_balance;
+///
+/// assert_eq!(checked_add_balance(2, 3), Some(5));
+/// assert_eq!(checked_add_balance(u128::MAX, 1), None);
+/// ```
+pub fn checked_add_balance(a: Balance, b: Balance) -> Option<Balance> {
+    a.checked_add(b)
+}
+
+/// Subtracts `b` from `a`, returning `None` instead of underflowing, see [`checked_add_balance`].
+///
+/// ```
+/// use near_sdk::checked_sub_balance;
+///
+/// assert_eq!(checked_sub_balance(5, 3), Some(2));
+/// assert_eq!(checked_sub_balance(0, 1), None);
+/// ```
+pub fn checked_sub_balance(a: Balance, b: Balance) -> Option<Balance> {
+    a.checked_sub(b)
+}
+
+/// A [`Balance`] whose arithmetic can't overflow: there are no arithmetic operators, only the
+/// checked methods, so every addition and subtraction has to handle the overflow.
+///
+/// ```
+/// use near_sdk::CheckedBalance;
+///
+/// let balance = CheckedBalance(100);
+/// assert_eq!(balance.checked_sub(30), Some(CheckedBalance(70)));
+/// assert_eq!(balance.checked_sub(101), None);
+/// ```
+///
+/// In Borsh the balance is serialized as `u128`.
+#[derive(
+    Default,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    PartialOrd,
+    Ord,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Hash,
+    BorshSchema,
+)]
+#[repr(transparent)]
+pub struct CheckedBalance(pub Balance);
+
+impl CheckedBalance {
+    /// Returns the sum, or `None` if it overflows.
+    pub fn checked_add(self, amount: Balance) -> Option<Self> {
+        checked_add_balance(self.0, amount).map(Self)
+    }
+
+    /// Returns the difference, or `None` if `amount` is greater than the balance.
+    pub fn checked_sub(self, amount: Balance) -> Option<Self> {
+        checked_sub_balance(self.0, amount).map(Self)
+    }
+}
+
+impl From<Balance> for CheckedBalance {
+    fn from(amount: Balance) -> Self {
+        Self(amount)
+    }
+}
+
+impl From<CheckedBalance> for Balance {
+    fn from(balance: CheckedBalance) -> Self {
+        balance.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_at_max() {
+        assert_eq!(checked_add_balance(u128::MAX, 0), Some(u128::MAX));
+        assert_eq!(checked_add_balance(u128::MAX - 1, 1), Some(u128::MAX));
+        assert_eq!(checked_add_balance(u128::MAX, 1), None);
+        assert_eq!(checked_add_balance(u128::MAX, u128::MAX), None);
+
+        assert_eq!(CheckedBalance(u128::MAX - 1).checked_add(1), Some(CheckedBalance(u128::MAX)));
+        assert_eq!(CheckedBalance(u128::MAX).checked_add(1), None);
+    }
+
+    #[test]
+    fn sub_at_zero() {
+        assert_eq!(checked_sub_balance(u128::MAX, u128::MAX), Some(0));
+        assert_eq!(checked_sub_balance(1, 1), Some(0));
+        assert_eq!(checked_sub_balance(0, 1), None);
+        assert_eq!(checked_sub_balance(1, u128::MAX), None);
+
+        assert_eq!(CheckedBalance(1).checked_sub(1), Some(CheckedBalance(0)));
+        assert_eq!(CheckedBalance(0).checked_sub(u128::MAX), None);
+    }
+
+    #[test]
+    fn borsh_ser() {
+        let balance = CheckedBalance(u128::MAX);
+        let ser = balance.try_to_vec().unwrap();
+        assert_eq!(ser, u128::MAX.to_le_bytes().to_vec());
+        assert_eq!(CheckedBalance::try_from_slice(&ser).unwrap(), balance);
+        assert_eq!(Balance::from(balance), u128::MAX);
+    }
+}
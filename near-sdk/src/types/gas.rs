@@ -3,6 +3,20 @@ use core::ops;
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 
 /// Represents the amount of NEAR tokens in "gas units" which are used to fund transactions.
+///
+/// The arithmetic operators saturate instead of overflowing, so computing the gas left for a
+/// callback can't panic and yields zero when not enough gas is left:
+/// ```
+/// use near_sdk::Gas;
+///
+/// let prepaid = Gas::from_tgas(30);
+/// let used = Gas::from_tgas(20);
+/// let reserve = Gas::from_tgas(15);
+/// assert_eq!(prepaid - used - reserve, Gas(0));
+/// assert_eq!(Gas(u64::MAX) + Gas::ONE_TERA, Gas(u64::MAX));
+/// ```
+///
+/// In JSON the amount is serialized as a decimal string, in Borsh as `u64`.
 #[derive(
     Default,
     Debug,
@@ -20,6 +34,19 @@ use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 #[repr(transparent)]
 pub struct Gas(pub u64);
 
+impl Gas {
+    /// One Tgas, which is 10^12 gas.
+    pub const ONE_TERA: Gas = Gas(1_000_000_000_000);
+
+    /// One Ggas, which is 10^9 gas.
+    pub const ONE_GIGA: Gas = Gas(1_000_000_000);
+
+    /// Creates the amount of `tgas` Tgas, saturating at `u64::MAX`.
+    pub const fn from_tgas(tgas: u64) -> Self {
+        Self(tgas.saturating_mul(Self::ONE_TERA.0))
+    }
+}
+
 impl Serialize for Gas {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -66,19 +93,19 @@ impl ops::Add for Gas {
     type Output = Self;
 
     fn add(self, other: Self) -> Self {
-        Self(self.0 + other.0)
+        Self(self.0.saturating_add(other.0))
     }
 }
 
 impl ops::AddAssign for Gas {
     fn add_assign(&mut self, other: Self) {
-        self.0 += other.0;
+        *self = *self + other;
     }
 }
 
 impl ops::SubAssign for Gas {
     fn sub_assign(&mut self, other: Self) {
-        self.0 -= other.0;
+        *self = *self - other;
     }
 }
 
@@ -86,7 +113,7 @@ impl ops::Sub for Gas {
     type Output = Self;
 
     fn sub(self, other: Self) -> Self {
-        Self(self.0 - other.0)
+        Self(self.0.saturating_sub(other.0))
     }
 }
 
@@ -94,7 +121,7 @@ impl ops::Mul<u64> for Gas {
     type Output = Self;
 
     fn mul(self, other: u64) -> Self {
-        Self(self.0 * other)
+        Self(self.0.saturating_mul(other))
     }
 }
 
@@ -132,4 +159,43 @@ mod tests {
         test_json_ser(8);
         test_json_ser(0);
     }
+
+    #[test]
+    fn borsh_ser() {
+        let gas = Gas(u64::MAX - 1);
+        let ser = gas.try_to_vec().unwrap();
+        assert_eq!(ser, (u64::MAX - 1).to_le_bytes().to_vec());
+        assert_eq!(Gas::try_from_slice(&ser).unwrap(), gas);
+    }
+
+    #[test]
+    fn saturating_ops() {
+        assert_eq!(Gas(u64::MAX) + Gas(1), Gas(u64::MAX));
+        assert_eq!(Gas(1) - Gas(2), Gas(0));
+        assert_eq!(Gas(u64::MAX / 2 + 1) * 2, Gas(u64::MAX));
+        assert_eq!(Gas(7) / 2, Gas(3));
+        assert_eq!(Gas(7) % 2, Gas(1));
+        assert_eq!(Gas(2) + Gas(3) - Gas(1), Gas(4));
+
+        let mut gas = Gas(u64::MAX - 1);
+        gas += Gas(5);
+        assert_eq!(gas, Gas(u64::MAX));
+        gas -= Gas(u64::MAX);
+        gas -= Gas(1);
+        assert_eq!(gas, Gas(0));
+
+        let prepaid = Gas::from_tgas(300);
+        let reserve = Gas::from_tgas(5);
+        assert_eq!(prepaid - Gas::from_tgas(290) - reserve, Gas::from_tgas(5));
+        assert_eq!(prepaid - Gas::from_tgas(299) - reserve, Gas(0));
+    }
+
+    #[test]
+    fn constants() {
+        assert_eq!(Gas::ONE_TERA, Gas(1_000_000_000_000));
+        assert_eq!(Gas::ONE_GIGA * 1000, Gas::ONE_TERA);
+        assert_eq!(Gas::from_tgas(5), Gas(5_000_000_000_000));
+        assert_eq!(Gas::from_tgas(0), Gas(0));
+        assert_eq!(Gas::from_tgas(u64::MAX), Gas(u64::MAX));
+    }
 }
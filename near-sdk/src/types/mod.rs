@@ -8,11 +8,15 @@ mod primitives;
 pub use self::primitives::*;
 
 mod account_id;
-pub use self::account_id::{AccountId, ParseAccountIdError};
+pub(crate) use self::account_id::validate_account_id;
+pub use self::account_id::{AccountId, AccountIdRef, ParseAccountIdError, ParseAccountIdErrorKind};
 
 mod gas;
 pub use self::gas::Gas;
 
+mod balance;
+pub use self::balance::{checked_add_balance, checked_sub_balance, CheckedBalance};
+
 /// Raw type for duration in nanoseconds
 pub type Duration = u64;
 
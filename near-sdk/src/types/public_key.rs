@@ -112,6 +112,12 @@ impl From<PublicKey> for Vec<u8> {
     }
 }
 
+impl AsRef<[u8]> for PublicKey {
+    fn as_ref(&self) -> &[u8] {
+        &self.data
+    }
+}
+
 impl TryFrom<Vec<u8>> for PublicKey {
     type Error = ParsePublicKeyError;
 
@@ -248,6 +254,24 @@ mod tests {
         assert_eq!(actual, "ed25519:6E8sCci9badyRkXb3JoRpBj5p8C6Tw41ELDZoiihKEtp");
     }
 
+    #[test]
+    fn test_public_key_wrong_length() {
+        let mut data = expected_key().into_bytes();
+        data.pop();
+        assert!(PublicKey::try_from(data).is_err());
+        assert!(PublicKey::try_from(Vec::new()).is_err());
+        let short = bs58::encode(&[1u8; 31]).into_string();
+        assert!(PublicKey::from_str(&format!("ed25519:{}", short)).is_err());
+    }
+
+    #[test]
+    fn test_public_key_borsh_round_trip() {
+        let key = expected_key();
+        let encoded = key.try_to_vec().unwrap();
+        assert_eq!(&encoded[4..], key.as_ref());
+        assert_eq!(PublicKey::try_from_slice(&encoded).unwrap(), key);
+    }
+
     #[test]
     fn test_public_key_borsh_format_change() {
         // Original struct to reference Borsh serialization from
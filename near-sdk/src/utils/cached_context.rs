@@ -0,0 +1,107 @@
+use crate::{env, AccountId, Balance};
+
+/// Reads the fields of the execution context from the host at most once and lends them out.
+/// [`env::current_account_id`] and [`env::predecessor_account_id`] are already read once per
+/// execution, but every call still clones the [`AccountId`], which adds up in methods that check
+/// the caller in several guards. [`env::signer_account_id`] and [`env::attached_deposit`] are not
+/// cached by `env` and reach the host on every call. The fields are read on the first access, so
+/// creating the context is free.
+///
+/// The context is only valid within the method that created it, don't store it in the contract
+/// state.
+///
+/// # Examples
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::{env, CachedContext};
+///
+/// let mut context = CachedContext::new();
+/// assert_eq!(context.signer_account_id(), &env::signer_account_id());
+/// assert_eq!(context.attached_deposit(), env::attached_deposit());
+/// ```
+#[derive(Default)]
+pub struct CachedContext {
+    current_account_id: Option<AccountId>,
+    signer_account_id: Option<AccountId>,
+    predecessor_account_id: Option<AccountId>,
+    attached_deposit: Option<Balance>,
+}
+
+impl CachedContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// See [`env::current_account_id`].
+    pub fn current_account_id(&mut self) -> &AccountId {
+        self.current_account_id.get_or_insert_with(env::current_account_id)
+    }
+
+    /// See [`env::signer_account_id`].
+    pub fn signer_account_id(&mut self) -> &AccountId {
+        self.signer_account_id.get_or_insert_with(env::signer_account_id)
+    }
+
+    /// See [`env::predecessor_account_id`].
+    pub fn predecessor_account_id(&mut self) -> &AccountId {
+        self.predecessor_account_id.get_or_insert_with(env::predecessor_account_id)
+    }
+
+    /// See [`env::attached_deposit`].
+    pub fn attached_deposit(&mut self) -> Balance {
+        *self.attached_deposit.get_or_insert_with(env::attached_deposit)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::test_env::{alice, bob};
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    #[test]
+    fn test_fields() {
+        testing_env!(VMContextBuilder::new()
+            .current_account_id(alice())
+            .signer_account_id(bob())
+            .predecessor_account_id(bob())
+            .attached_deposit(5)
+            .build());
+        let mut context = CachedContext::new();
+        assert_eq!(context.current_account_id(), &alice());
+        assert_eq!(context.signer_account_id(), &bob());
+        assert_eq!(context.predecessor_account_id(), &bob());
+        assert_eq!(context.attached_deposit(), 5);
+    }
+
+    #[test]
+    fn test_reads_host_once() {
+        testing_env!(VMContextBuilder::new().signer_account_id(bob()).attached_deposit(5).build());
+        // The mocked host charges gas for every call, so the used gas shows which calls reach it.
+        let used_gas_cost = {
+            let before = env::used_gas();
+            env::used_gas().0 - before.0
+        };
+        let mut context = CachedContext::new();
+
+        let before = env::used_gas();
+        assert_eq!(context.signer_account_id(), &bob());
+        assert_eq!(context.attached_deposit(), 5);
+        let first_read = env::used_gas().0 - before.0;
+        assert!(first_read > used_gas_cost);
+
+        let before = env::used_gas();
+        for _ in 0..3 {
+            assert_eq!(context.signer_account_id(), &bob());
+            assert_eq!(context.attached_deposit(), 5);
+        }
+        assert_eq!(env::used_gas().0 - before.0, used_gas_cost);
+
+        // Unlike the context, `env` asks the host again.
+        let before = env::used_gas();
+        assert_eq!(env::signer_account_id(), bob());
+        assert!(env::used_gas().0 - before.0 > used_gas_cost);
+    }
+}
@@ -0,0 +1,96 @@
+use serde::Serialize;
+
+use crate::env;
+
+/// Prefix of the logs that contain events, so that indexers can tell them apart from other logs.
+pub const EVENT_JSON_PREFIX: &str = "EVENT_JSON:";
+
+/// An event in the format of [NEP-297](https://nomicon.io/Standards/EventsFormat), which is logged
+/// as `EVENT_JSON:` followed by the JSON of the event.
+///
+/// # Examples
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::EventLog;
+/// use near_sdk::serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// #[serde(crate = "near_sdk::serde")]
+/// struct FtMint<'a> {
+///     owner_id: &'a str,
+///     amount: &'a str,
+/// }
+///
+/// let event = EventLog::new("nep141", "1.0.0", "ft_mint", [FtMint { owner_id: "alice", amount: "100" }]);
+/// assert_eq!(
+///     event.to_log_string(),
+///     r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_mint","data":[{"owner_id":"alice","amount":"100"}]}"#
+/// );
+/// event.emit();
+/// ```
+#[derive(Serialize)]
+pub struct EventLog<'a, T: Serialize> {
+    /// Name of the standard, e.g. `nep171`.
+    pub standard: &'a str,
+    /// Version of the standard, e.g. `1.0.0`.
+    pub version: &'a str,
+    /// Type of the event, e.g. `nft_mint`.
+    pub event: &'a str,
+    /// Data of the event, usually a list of objects.
+    pub data: T,
+}
+
+impl<'a, T: Serialize> EventLog<'a, T> {
+    pub fn new(standard: &'a str, version: &'a str, event: &'a str, data: T) -> Self {
+        Self { standard, version, event, data }
+    }
+
+    /// Returns the log line of the event, including the `EVENT_JSON:` prefix.
+    pub fn to_log_string(&self) -> String {
+        let json = serde_json::to_string(self)
+            .unwrap_or_else(|_| env::panic(b"Failed to serialize the event"));
+        format!("{}{}", EVENT_JSON_PREFIX, json)
+    }
+
+    /// Logs the event.
+    pub fn emit(&self) {
+        env::log_str(&self.to_log_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::json_types::U128;
+    use crate::test_utils::{get_logs, test_env};
+    use crate::AccountId;
+
+    #[derive(Serialize)]
+    struct FtTransfer {
+        old_owner_id: AccountId,
+        new_owner_id: AccountId,
+        amount: U128,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        memo: Option<String>,
+    }
+
+    #[test]
+    fn test_transfer_event() {
+        test_env::setup();
+        let data = vec![FtTransfer {
+            old_owner_id: "alice.near".parse().unwrap(),
+            new_owner_id: "bob.near".parse().unwrap(),
+            amount: U128(1_000_000_000_000_000_000_000_000),
+            memo: None,
+        }];
+        EventLog::new("nep141", "1.0.0", "ft_transfer", data).emit();
+        assert_eq!(
+            get_logs(),
+            vec![concat!(
+                r#"EVENT_JSON:{"standard":"nep141","version":"1.0.0","event":"ft_transfer","#,
+                r#""data":[{"old_owner_id":"alice.near","new_owner_id":"bob.near","#,
+                r#""amount":"1000000000000000000000000"}]}"#
+            )]
+        );
+    }
+}
@@ -1,11 +1,25 @@
 pub(crate) mod storage_key_impl;
 
+mod cached_context;
+pub use cached_context::CachedContext;
+
+mod event;
+pub use event::{EventLog, EVENT_JSON_PREFIX};
+
+mod return_buffer;
+pub use return_buffer::ReturnBuffer;
+
+mod rng;
+pub use rng::Rng;
+
 #[cfg(feature = "unstable")]
 mod cache_entry;
 #[cfg(feature = "unstable")]
 pub(crate) use cache_entry::{CacheEntry, EntryState};
 
-use crate::{env, AccountId, PromiseResult};
+use crate::{
+    env, AccountId, Balance, Gas, Promise, PromiseResult, StorageUsage, StorageUsageChange,
+};
 
 /// Helper macro to log a message through [`env::log`].
 /// This macro can be used similar to the [`std::format`] macro in most cases.
@@ -40,6 +54,43 @@ macro_rules! log {
     };
 }
 
+/// Panics through [`env::panic_str`] with the message if the condition is false. The message
+/// takes the same arguments as [`std::format`], and is only formatted when the condition is false.
+/// Without a message, panics with "require! assertion failed".
+///
+/// # Examples
+///
+/// ```no_run
+/// use near_sdk::{env, require};
+///
+/// # fn main() {
+/// let deposit = env::attached_deposit();
+/// require!(deposit > 0);
+/// require!(deposit > 0, "Requires a deposit");
+/// require!(deposit >= 10, "Requires a deposit of 10 yoctoNEAR, got {}", deposit);
+/// # }
+/// ```
+///
+/// [`env::panic_str`]: crate::env::panic_str
+#[macro_export]
+macro_rules! require {
+    ($cond:expr $(,)?) => {
+        if !$cond {
+            $crate::env::panic_str("require! assertion failed")
+        }
+    };
+    ($cond:expr, $message:expr $(,)?) => {
+        if !$cond {
+            $crate::env::panic_str(&$message)
+        }
+    };
+    ($cond:expr, $($arg:tt)*) => {
+        if !$cond {
+            $crate::env::panic_str(&format!($($arg)*))
+        }
+    };
+}
+
 /// Assert that predecessor_account_id == current_account_id, meaning contract called itself.
 pub fn assert_self() {
     assert_eq!(env::predecessor_account_id(), env::current_account_id(), "Method is private");
@@ -50,6 +101,22 @@ pub fn assert_one_yocto() {
     assert_eq!(env::attached_deposit(), 1, "Requires attached deposit of exactly 1 yoctoNEAR")
 }
 
+/// Assert that at least 1 yoctoNEAR was attached.
+pub fn assert_at_least_one_yocto() {
+    assert!(env::attached_deposit() >= 1, "Requires attached deposit of at least 1 yoctoNEAR")
+}
+
+/// Assert that at least `min` yoctoNEAR was attached.
+pub fn assert_min_deposit(min: Balance) {
+    let attached = env::attached_deposit();
+    assert!(
+        attached >= min,
+        "Requires attached deposit of at least {} yoctoNEAR, but {} yoctoNEAR was attached",
+        min,
+        attached
+    )
+}
+
 /// Returns true if promise was successful.
 /// Fails if called outside a callback that received 1 promise result.
 pub fn is_promise_success() -> bool {
@@ -66,6 +133,127 @@ pub fn promise_result_as_success() -> Option<Vec<u8>> {
     }
 }
 
+/// Creates a promise that only succeeds if `account_id` exists.
+///
+/// The runtime does not expose a host function that checks whether an account exists, so the
+/// check has to be done with a cross-contract probe: the promise transfers 0 yoctoNEAR, which fails
+/// if the account doesn't exist. Chain a callback with `then` and check the result with
+/// [`is_promise_success`] in it. In unit tests the result of the probe can be staged with
+/// [`account_probe_result`].
+///
+/// Note that a transfer to an implicit account creates it, so the probe always succeeds for
+/// implicit account IDs.
+///
+/// [`account_probe_result`]: crate::test_utils::account_probe_result
+pub fn account_exists_probe(account_id: AccountId) -> Promise {
+    Promise::new(account_id).transfer(0)
+}
+
+/// Used by the code generated for `#[near_bindgen]` methods with the `debug-panic-context`
+/// feature. In debug builds calls `f` and re-raises its panic with the method name prepended.
+/// Panics can't be caught on `wasm32`, so there the method name is logged before calling `f`
+/// instead. Release builds just call `f`.
+#[doc(hidden)]
+#[allow(unused_variables)]
+pub fn with_method_context<R>(method_name: &str, f: impl FnOnce() -> R) -> R {
+    #[cfg(not(debug_assertions))]
+    {
+        f()
+    }
+    #[cfg(all(target_arch = "wasm32", debug_assertions))]
+    {
+        env::log_str(&format!("Calling method `{}`", method_name));
+        f()
+    }
+    #[cfg(all(not(target_arch = "wasm32"), debug_assertions))]
+    {
+        match std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)) {
+            Ok(res) => res,
+            Err(err) => {
+                let message = err
+                    .downcast_ref::<String>()
+                    .map(String::as_str)
+                    .or_else(|| err.downcast_ref::<&str>().copied())
+                    .unwrap_or("Box<Any>");
+                panic!("Method `{}` panicked: {}", method_name, message)
+            }
+        }
+    }
+}
+
+/// Used by the code generated for `#[near_bindgen]` methods with the `debug-return-size` feature
+/// in debug builds. Logs the size of the serialized value returned by the method, and records it
+/// in the [`MockedBlockchain`](crate::MockedBlockchain) in unit tests.
+#[doc(hidden)]
+pub fn record_return_size(method_name: &str, size: usize) {
+    env::log_str(&format!("Method `{}` returned {} bytes", method_name, size));
+    #[cfg(not(target_arch = "wasm32"))]
+    crate::mock::with_mocked_blockchain(|b| b.record_return_size(method_name, size));
+}
+
+/// Measures how much the storage used by the contract changed since the tracker was started. Useful
+/// to charge for the storage added by an operation or to refund the storage it released.
+///
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::StorageTracker;
+///
+/// let tracker = StorageTracker::start();
+/// near_sdk::env::storage_write(b"key", b"value");
+/// assert!(tracker.finish() > 0);
+/// ```
+pub struct StorageTracker {
+    initial_storage_usage: StorageUsage,
+}
+
+impl StorageTracker {
+    /// Starts tracking from the current storage usage.
+    pub fn start() -> Self {
+        Self { initial_storage_usage: env::storage_usage() }
+    }
+
+    /// Returns the change of the storage usage in bytes since the tracker was started. The change
+    /// is negative if storage was released.
+    pub fn finish(self) -> StorageUsageChange {
+        env::storage_usage() as StorageUsageChange
+            - self.initial_storage_usage as StorageUsageChange
+    }
+}
+
+/// Used by the code generated for `#[near_bindgen(max_gas = ...)]` methods in debug builds.
+/// Panics when dropped if more than `max_gas` was used since the guard was created.
+#[doc(hidden)]
+pub struct MaxGasGuard {
+    method_name: &'static str,
+    max_gas: Gas,
+    gas_at_start: Gas,
+}
+
+impl MaxGasGuard {
+    pub fn new(method_name: &'static str, max_gas: Gas) -> Self {
+        Self { method_name, max_gas, gas_at_start: env::used_gas() }
+    }
+}
+
+impl Drop for MaxGasGuard {
+    fn drop(&mut self) {
+        // Don't panic again if the method has already panicked.
+        if std::thread::panicking() {
+            return;
+        }
+        let used_gas = env::used_gas() - self.gas_at_start;
+        if used_gas > self.max_gas {
+            env::panic(
+                format!(
+                    "Method {} used {} gas, which exceeds its budget of {} gas",
+                    self.method_name, used_gas.0, self.max_gas.0
+                )
+                .as_bytes(),
+            )
+        }
+    }
+}
+
 /// Used in the simulation code generator from near_sdk.
 #[derive(Debug)]
 pub struct PendingContractTx {
@@ -117,7 +305,33 @@ macro_rules! setup_alloc {
 
 #[cfg(test)]
 mod tests {
-    use crate::test_utils::{get_logs, test_env};
+    use crate::collections::UnorderedMap;
+    use crate::test_utils::{
+        account_probe_result, accounts, get_created_receipts, get_logs, get_return_sizes, test_env,
+        VMContextBuilder,
+    };
+    use crate::{
+        account_exists_probe, assert_at_least_one_yocto, assert_min_deposit, assert_one_yocto, env,
+        is_promise_success, record_return_size, testing_env, with_method_context, Gas, MaxGasGuard,
+        StorageTracker,
+    };
+
+    #[test]
+    fn test_record_return_size() {
+        test_env::setup();
+        // The same steps as in the code generated for a method that returns a value.
+        let result = serde_json::to_vec(&vec!["alice.near", "bob.near"]).unwrap();
+        env::value_return(&result);
+        record_return_size("get_accounts", result.len());
+
+        let returned = match crate::mock::with_mocked_blockchain(|b| b.outcome().return_data) {
+            near_vm_logic::ReturnData::Value(value) => value,
+            _ => panic!("Expected a returned value"),
+        };
+        assert_eq!(returned.len(), 25);
+        assert_eq!(get_return_sizes(), vec![("get_accounts".to_string(), returned.len())]);
+        assert_eq!(get_logs(), vec!["Method `get_accounts` returned 25 bytes".to_string()]);
+    }
 
     #[test]
     fn test_log_simple() {
@@ -134,4 +348,165 @@ mod tests {
 
         assert_eq!(get_logs(), vec!["hello user_name (25)".to_string()]);
     }
+
+    #[test]
+    fn test_assert_min_deposit() {
+        testing_env!(VMContextBuilder::new().attached_deposit(100));
+        assert_min_deposit(0);
+        assert_min_deposit(99);
+        assert_min_deposit(100);
+    }
+
+    #[test]
+    fn test_assert_one_yocto() {
+        testing_env!(VMContextBuilder::new().attached_deposit(1));
+        assert_one_yocto();
+        assert_at_least_one_yocto();
+        testing_env!(VMContextBuilder::new().attached_deposit(2));
+        assert_at_least_one_yocto();
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_assert_one_yocto_none() {
+        testing_env!(VMContextBuilder::new().attached_deposit(0));
+        assert_one_yocto();
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of exactly 1 yoctoNEAR")]
+    fn test_assert_one_yocto_more() {
+        testing_env!(VMContextBuilder::new().attached_deposit(2));
+        assert_one_yocto();
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires attached deposit of at least 1 yoctoNEAR")]
+    fn test_assert_at_least_one_yocto_none() {
+        testing_env!(VMContextBuilder::new().attached_deposit(0));
+        assert_at_least_one_yocto();
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "Requires attached deposit of at least 101 yoctoNEAR, but 100 yoctoNEAR was attached"
+    )]
+    fn test_assert_min_deposit_insufficient() {
+        testing_env!(VMContextBuilder::new().attached_deposit(100));
+        assert_min_deposit(101);
+    }
+
+    #[test]
+    fn test_with_method_context() {
+        assert_eq!(with_method_context("get", || 5), 5);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "Method `set` panicked: Value is too large")]
+    fn test_with_method_context_panic() {
+        with_method_context("set", || panic!("Value is too large {}", ""))
+    }
+
+    #[test]
+    fn test_storage_tracker() {
+        test_env::setup();
+        let mut map = UnorderedMap::new(b"m");
+        let tracker = StorageTracker::start();
+        map.insert(&1u64, &"value".to_string());
+        let added = tracker.finish();
+        assert!(added > 0);
+
+        let tracker = StorageTracker::start();
+        map.remove(&1u64);
+        assert_eq!(tracker.finish(), -added);
+
+        // Dropping the tracker without finishing it does nothing.
+        let _tracker = StorageTracker::start();
+    }
+
+    #[test]
+    fn test_account_exists_probe() {
+        test_env::setup();
+        let existing = vec![accounts(0), accounts(1)];
+        account_exists_probe(accounts(1));
+        account_exists_probe(accounts(2));
+        assert_eq!(get_created_receipts().len(), 2);
+
+        // The callback of the probe.
+        testing_env!(
+            VMContextBuilder::new(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            vec![account_probe_result(&existing, &accounts(1))],
+        );
+        assert!(is_promise_success());
+        testing_env!(
+            VMContextBuilder::new(),
+            Default::default(),
+            Default::default(),
+            Default::default(),
+            vec![account_probe_result(&existing, &accounts(2))],
+        );
+        assert!(!is_promise_success());
+    }
+
+    #[test]
+    fn test_max_gas_guard_within_budget() {
+        test_env::setup();
+        let _guard = MaxGasGuard::new("method", Gas(10u64.pow(12)));
+        env::storage_write(b"key", b"value");
+    }
+
+    #[test]
+    #[should_panic(expected = "Method method used")]
+    fn test_max_gas_guard_exceeded() {
+        test_env::setup();
+        let _guard = MaxGasGuard::new("method", Gas(10u64.pow(9)));
+        env::storage_write(b"key", b"value");
+    }
+
+    #[test]
+    fn test_require_holds() {
+        use std::cell::Cell;
+        use std::fmt;
+
+        struct Counted<'a>(&'a Cell<u32>);
+        impl fmt::Display for Counted<'_> {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.set(self.0.get() + 1);
+                write!(f, "counted")
+            }
+        }
+
+        test_env::setup();
+        let formatted = Cell::new(0);
+        crate::require!(true);
+        crate::require!(1 + 1 == 2, "Math is broken");
+        crate::require!(true, "Not formatted: {}", Counted(&formatted));
+        assert_eq!(formatted.get(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires 10, got 5")]
+    fn test_require_formatted_message() {
+        test_env::setup();
+        let value = 5;
+        crate::require!(value >= 10, "Requires {}, got {}", 10, value);
+    }
+
+    #[test]
+    #[should_panic(expected = "Requires a deposit")]
+    fn test_require_message() {
+        test_env::setup();
+        crate::require!(env::attached_deposit() > 0, "Requires a deposit");
+    }
+
+    #[test]
+    #[should_panic(expected = "require! assertion failed")]
+    fn test_require_without_message() {
+        test_env::setup();
+        crate::require!(false);
+    }
 }
@@ -0,0 +1,110 @@
+use crate::env;
+
+/// Accumulates a large return value that is built piece by piece and returns it with a single
+/// [`env::value_return`] on [`finish`](Self::finish).
+///
+/// The host checks the length of the return value only when `value_return` is called, after the
+/// whole value was built. The buffer has a limit of its own and panics with a clear message as
+/// soon as a write goes over it. The limit of the NEAR protocol is 4 MiB,
+/// [`DEFAULT_LIMIT`](Self::DEFAULT_LIMIT).
+///
+/// # Examples
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::ReturnBuffer;
+///
+/// let mut buffer = ReturnBuffer::new(1024);
+/// for chunk in [b"abc", b"def"].iter() {
+///     buffer.write(*chunk);
+/// }
+/// assert_eq!(buffer.len(), 6);
+/// buffer.finish();
+/// ```
+pub struct ReturnBuffer {
+    data: Vec<u8>,
+    limit: usize,
+}
+
+impl ReturnBuffer {
+    /// The maximum length of a return value accepted by the NEAR protocol.
+    pub const DEFAULT_LIMIT: usize = 4 << 20;
+
+    /// Creates an empty buffer that holds at most `limit` bytes.
+    pub fn new(limit: usize) -> Self {
+        Self { data: Vec::new(), limit }
+    }
+
+    /// Appends `bytes` to the return value.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the return value would exceed the limit of the buffer.
+    pub fn write(&mut self, bytes: &[u8]) {
+        let len = self.data.len().saturating_add(bytes.len());
+        if len > self.limit {
+            env::panic(
+                format!(
+                    "The return value of {} bytes exceeds the limit of {} bytes",
+                    len, self.limit
+                )
+                .as_bytes(),
+            )
+        }
+        self.data.extend_from_slice(bytes);
+    }
+
+    /// Returns the number of bytes written so far.
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns `true` if nothing was written yet.
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the accumulated bytes with [`env::value_return`].
+    pub fn finish(self) {
+        env::value_return(&self.data)
+    }
+}
+
+impl Default for ReturnBuffer {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_LIMIT)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::with_mocked_blockchain;
+    use crate::test_utils::test_env;
+    use near_vm_logic::ReturnData;
+
+    #[test]
+    fn test_under_limit() {
+        test_env::setup();
+        let mut buffer = ReturnBuffer::new(6);
+        assert!(buffer.is_empty());
+        buffer.write(b"abc");
+        buffer.write(b"");
+        buffer.write(b"def");
+        assert_eq!(buffer.len(), 6);
+        buffer.finish();
+        assert!(matches!(
+            with_mocked_blockchain(|b| b.outcome().return_data),
+            ReturnData::Value(value) if value == b"abcdef"
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "The return value of 7 bytes exceeds the limit of 6 bytes")]
+    fn test_over_limit() {
+        test_env::setup();
+        let mut buffer = ReturnBuffer::new(6);
+        buffer.write(b"abcd");
+        buffer.write(b"efg");
+    }
+}
@@ -0,0 +1,179 @@
+use crate::env;
+
+/// A pseudo-random number generator seeded from [`env::random_seed`]. All nodes that execute the
+/// contract get the same seed, so the numbers are deterministic for a given seed and tests can
+/// reproduce them by setting `random_seed` in the `VMContext`.
+///
+/// The numbers are generated with xoshiro256**, whose state is derived from the seed with
+/// SplitMix64. The seed is known to the validators, so don't use the generator for anything a
+/// validator could profit from predicting.
+///
+/// # Examples
+/// ```
+/// # near_sdk::test_utils::test_env::setup();
+/// use near_sdk::Rng;
+///
+/// let mut rng = Rng::new();
+/// let winner = rng.gen_range(0, 10);
+/// assert!(winner < 10);
+/// ```
+pub struct Rng {
+    state: [u64; 4],
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+impl Rng {
+    /// Creates a generator seeded from [`env::random_seed`].
+    pub fn new() -> Self {
+        Self::from_seed(&env::random_seed())
+    }
+
+    /// Creates a generator from the given seed, which can have any length.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut mix = 0u64;
+        for chunk in seed.chunks(8) {
+            let mut word = [0u8; 8];
+            word[..chunk.len()].copy_from_slice(chunk);
+            mix ^= u64::from_le_bytes(word);
+            splitmix64(&mut mix);
+        }
+        let mut state = [0u64; 4];
+        for word in state.iter_mut() {
+            *word = splitmix64(&mut mix);
+        }
+        Self { state }
+    }
+
+    /// Returns the next random `u64`.
+    pub fn next_u64(&mut self) -> u64 {
+        let result = self.state[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = self.state[1] << 17;
+        self.state[2] ^= self.state[0];
+        self.state[3] ^= self.state[1];
+        self.state[1] ^= self.state[2];
+        self.state[0] ^= self.state[3];
+        self.state[2] ^= t;
+        self.state[3] = self.state[3].rotate_left(45);
+        result
+    }
+
+    /// Returns a random number in `low..high`. Every number in the range is equally likely:
+    /// instead of taking the remainder of a random `u64`, which favors the small numbers, the
+    /// values that would cause the bias are rejected and drawn again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `low >= high`.
+    pub fn gen_range(&mut self, low: u64, high: u64) -> u64 {
+        if low >= high {
+            env::panic(b"The range of gen_range is empty")
+        }
+        let range = high - low;
+        // The number of values at the start of the `u64` range that have to be rejected, so that
+        // the number of remaining values is a multiple of `range`.
+        let threshold = range.wrapping_neg() % range;
+        loop {
+            let value = self.next_u64();
+            if value >= threshold {
+                return low + value % range;
+            }
+        }
+    }
+
+    /// Shuffles the slice in place with the Fisher-Yates shuffle, so every order is equally
+    /// likely.
+    pub fn shuffle<T>(&mut self, values: &mut [T]) {
+        for i in (1..values.len()).rev() {
+            let j = self.gen_range(0, i as u64 + 1) as usize;
+            values.swap(i, j);
+        }
+    }
+}
+
+impl Default for Rng {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::VMContextBuilder;
+    use crate::testing_env;
+
+    #[test]
+    fn test_deterministic() {
+        testing_env!(VMContextBuilder::new().random_seed(vec![7; 32]).build());
+        let mut rng = Rng::new();
+        let values: Vec<u64> = (0..5).map(|_| rng.gen_range(0, 100)).collect();
+        assert_eq!(values, vec![84, 33, 95, 37, 82]);
+
+        let mut rng = Rng::new();
+        let again: Vec<u64> = (0..5).map(|_| rng.gen_range(0, 100)).collect();
+        assert_eq!(again, values);
+
+        let mut other = Rng::from_seed(&[8; 32]);
+        assert_ne!(other.next_u64(), Rng::from_seed(&[7; 32]).next_u64());
+
+        let mut values: Vec<u32> = (0..10).collect();
+        Rng::from_seed(&[7; 32]).shuffle(&mut values);
+        assert_eq!(values, vec![1, 5, 6, 2, 9, 0, 3, 8, 7, 4]);
+    }
+
+    #[test]
+    fn test_reference_output() {
+        // The first outputs of the reference implementation of xoshiro256** for this state.
+        let mut rng = Rng { state: [1, 2, 3, 4] };
+        let values: Vec<u64> = (0..4).map(|_| rng.next_u64()).collect();
+        assert_eq!(values, vec![11520, 0, 1509978240, 1215971899390074240]);
+    }
+
+    #[test]
+    fn test_gen_range_bounds() {
+        let mut rng = Rng::from_seed(&[0; 32]);
+        let mut counts = [0u32; 6];
+        for _ in 0..6000 {
+            let value = rng.gen_range(10, 16);
+            assert!((10..16).contains(&value));
+            counts[(value - 10) as usize] += 1;
+        }
+        // Every value is drawn about 1000 times.
+        for count in counts.iter() {
+            assert!(*count > 850 && *count < 1150, "{:?}", counts);
+        }
+
+        assert_eq!(rng.gen_range(5, 6), 5);
+        let value = rng.gen_range(u64::MAX - 1, u64::MAX);
+        assert_eq!(value, u64::MAX - 1);
+        let value = rng.gen_range(0, u64::MAX);
+        assert!(value < u64::MAX);
+    }
+
+    #[test]
+    fn test_shuffle() {
+        let mut rng = Rng::from_seed(b"seed");
+        let mut values: Vec<u32> = (0..50).collect();
+        rng.shuffle(&mut values);
+        assert_ne!(values, (0..50).collect::<Vec<_>>());
+        values.sort_unstable();
+        assert_eq!(values, (0..50).collect::<Vec<_>>());
+
+        let mut empty: [u32; 0] = [];
+        rng.shuffle(&mut empty);
+    }
+
+    #[test]
+    #[should_panic(expected = "The range of gen_range is empty")]
+    fn test_gen_range_empty() {
+        Rng::from_seed(&[0; 32]).gen_range(3, 3);
+    }
+}
@@ -0,0 +1,95 @@
+//! Checks the JSON ABI generated with the `abi` feature, run with `cargo test --features abi`.
+#![cfg(feature = "abi")]
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{near_bindgen, AccountId, PanicOnDefault};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Counter {
+    owner: AccountId,
+    value: u64,
+}
+
+#[near_bindgen]
+impl Counter {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self { owner, value: 0 }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+
+    #[payable]
+    pub fn add(&mut self, by: u64, memo: Option<String>) {
+        let _ = memo;
+        self.value += by;
+    }
+
+    #[allow(dead_code)]
+    fn internal(&self) {}
+}
+
+#[near_bindgen]
+impl Counter {
+    pub fn reset(&mut self) {
+        self.value = 0;
+    }
+}
+
+pub trait Ownable {
+    fn owner(&self) -> AccountId;
+}
+
+#[near_bindgen]
+impl Ownable for Counter {
+    fn owner(&self) -> AccountId {
+        self.owner.clone()
+    }
+}
+
+#[test]
+fn contract_abi() {
+    let abi: serde_json::Value = serde_json::from_str(&__contract_abi_impl_new()).unwrap();
+    let expected = serde_json::json!({
+        "methods": [
+            {
+                "name": "new",
+                "kind": "init",
+                "args": [{ "name": "owner", "type": "AccountId" }],
+                "returns": null
+            },
+            { "name": "get", "kind": "view", "args": [], "returns": "u64" },
+            {
+                "name": "add",
+                "kind": "call",
+                "args": [
+                    { "name": "by", "type": "u64" },
+                    { "name": "memo", "type": "Option<String>" }
+                ],
+                "returns": null
+            }
+        ]
+    });
+    assert_eq!(abi, expected);
+}
+
+#[test]
+fn second_impl_abi() {
+    let abi: serde_json::Value = serde_json::from_str(&__contract_abi_impl_reset()).unwrap();
+    let expected = serde_json::json!({
+        "methods": [{ "name": "reset", "kind": "call", "args": [], "returns": null }]
+    });
+    assert_eq!(abi, expected);
+}
+
+#[test]
+fn trait_abi() {
+    let abi: serde_json::Value = serde_json::from_str(&__contract_abi_ownable()).unwrap();
+    let expected = serde_json::json!({
+        "methods": [{ "name": "owner", "kind": "view", "args": [], "returns": "AccountId" }]
+    });
+    assert_eq!(abi, expected);
+}
@@ -0,0 +1,56 @@
+//! Checks that panics are reported with the method name, run with
+//! `cargo test --features debug-panic-context`.
+#![cfg(all(feature = "debug-panic-context", debug_assertions))]
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::{env, near_bindgen, PanicOnDefault};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Counter {
+    value: u64,
+}
+
+#[near_bindgen]
+impl Counter {
+    #[init]
+    pub fn new() -> Self {
+        Self { value: 0 }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+
+    pub fn add(&mut self, by: u64) {
+        self.value = self.value.checked_add(by).expect("Overflow");
+    }
+
+    pub fn fail(&self) {
+        env::panic(b"Always fails")
+    }
+}
+
+#[test]
+fn test_method_without_panic() {
+    near_sdk::test_utils::test_env::setup();
+    let mut contract = Counter::new();
+    contract.add(5);
+    assert_eq!(contract.get(), 5);
+}
+
+#[test]
+#[should_panic(expected = "Method `add` panicked: Overflow")]
+fn test_panic_has_method_name() {
+    near_sdk::test_utils::test_env::setup();
+    let mut contract = Counter::new();
+    contract.add(u64::MAX);
+    contract.add(1);
+}
+
+#[test]
+#[should_panic(expected = "Method `fail` panicked:")]
+fn test_env_panic_has_method_name() {
+    near_sdk::test_utils::test_env::setup();
+    Counter::new().fail();
+}
@@ -0,0 +1,51 @@
+//! Checks that methods marked with `#[deprecated_method]` log a warning and still run.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::near_bindgen;
+use near_sdk::test_utils::{get_logs, test_env};
+
+#[near_bindgen]
+#[derive(Default, BorshDeserialize, BorshSerialize)]
+pub struct Token {
+    transfers: u32,
+}
+
+#[near_bindgen]
+impl Token {
+    #[deprecated_method(use = "new_transfer")]
+    pub fn transfer(&mut self) -> u32 {
+        self.new_transfer()
+    }
+
+    #[deprecated_method]
+    pub fn burn(&mut self) {}
+
+    pub fn new_transfer(&mut self) -> u32 {
+        self.transfers += 1;
+        self.transfers
+    }
+}
+
+#[test]
+fn test_deprecated_method_logs_warning() {
+    test_env::setup();
+    let mut contract = Token::default();
+    assert_eq!(contract.transfer(), 1);
+    assert_eq!(contract.transfer(), 2);
+    assert_eq!(
+        get_logs(),
+        vec![
+            "Method transfer is deprecated, use new_transfer",
+            "Method transfer is deprecated, use new_transfer"
+        ]
+    );
+}
+
+#[test]
+fn test_deprecated_method_without_replacement() {
+    test_env::setup();
+    let mut contract = Token::default();
+    contract.burn();
+    assert_eq!(contract.new_transfer(), 1);
+    assert_eq!(get_logs(), vec!["Method burn is deprecated"]);
+}
@@ -0,0 +1,28 @@
+//! Checks that a `near_bindgen` enum can be stored as the contract state.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::test_utils::test_env;
+use near_sdk::{env, near_bindgen};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, Debug, PartialEq)]
+enum Auction {
+    Open { highest_bid: u128 },
+    Closed,
+}
+
+#[test]
+fn test_state_round_trip() {
+    test_env::setup();
+    let state = Auction::Open { highest_bid: 5 };
+    env::state_write(&state);
+    assert_eq!(env::storage_read(b"STATE").unwrap()[0], 0);
+    assert_eq!(env::state_read::<Auction>(), Some(state));
+
+    env::state_write(&Auction::Closed);
+    assert_eq!(env::storage_read(b"STATE"), Some(vec![1]));
+    assert_eq!(env::state_read::<Auction>(), Some(Auction::Closed));
+
+    // The proxy for simulation tests is generated like for a struct.
+    let _proxy = AuctionContract { account_id: "auction.near".parse().unwrap() };
+}
@@ -0,0 +1,121 @@
+//! Checks that the functions and the call builder generated by `ext_contract` serialize the
+//! arguments and attach the given deposit and gas to the function call.
+
+use near_sdk::json_types::U128;
+use near_sdk::serde::Serialize;
+use near_sdk::test_utils::{get_created_receipts, test_env};
+use near_sdk::{ext_contract, Gas};
+
+#[ext_contract(ext_ft)]
+pub trait FungibleToken {
+    fn ft_transfer(&mut self, receiver_id: AccountId, amount: U128, memo: Option<String>);
+    fn ft_total_supply(&self) -> U128;
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TransferArgs {
+    receiver_id: String,
+    amount: U128,
+}
+
+#[ext_contract(ext_flat)]
+pub trait FlatToken {
+    fn ft_transfer(&mut self, #[serde(flatten)] args: TransferArgs);
+}
+
+fn receipts() -> Vec<serde_json::Value> {
+    get_created_receipts()
+        .iter()
+        .map(|receipt| {
+            // `to_value` does not support the `u128` deposit, so go through the JSON string.
+            serde_json::from_str(&serde_json::to_string(receipt).unwrap()).unwrap()
+        })
+        .collect()
+}
+
+fn function_calls() -> Vec<serde_json::Value> {
+    receipts().iter().map(|receipt| receipt["actions"][0]["FunctionCall"].clone()).collect()
+}
+
+#[test]
+fn test_configured_call() {
+    test_env::setup();
+    ext_ft::ext("token.near".parse().unwrap(), Gas(5_000_000_000_000))
+        .with_attached_deposit(1)
+        .ft_transfer("bob.near".parse().unwrap(), U128(100), None);
+
+    let calls = function_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0]["method_name"], "ft_transfer");
+    assert_eq!(calls[0]["args"], r#"{"receiver_id":"bob.near","amount":"100","memo":null}"#);
+    assert_eq!(calls[0]["deposit"], 1);
+    assert_eq!(calls[0]["gas"], 5_000_000_000_000u64);
+}
+
+#[test]
+fn test_call_without_deposit() {
+    test_env::setup();
+    ext_ft::ext("token.near".parse().unwrap(), Gas(10)).ft_total_supply();
+
+    let calls = function_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0]["method_name"], "ft_total_supply");
+    assert_eq!(calls[0]["args"], "");
+    assert_eq!(calls[0]["deposit"], 0);
+    assert_eq!(calls[0]["gas"], 10);
+}
+
+#[test]
+fn test_call_with_arguments() {
+    test_env::setup();
+    let token: near_sdk::AccountId = "token.near".parse().unwrap();
+    ext_ft::ft_transfer("bob.near".parse().unwrap(), U128(5), None, &token, 1, Gas(10));
+    ext_ft::ft_total_supply(&"other.near", 0, Gas(20));
+
+    let receivers: Vec<serde_json::Value> =
+        receipts().iter().map(|receipt| receipt["receiver_id"].clone()).collect();
+    assert_eq!(receivers, vec!["token.near", "other.near"]);
+    let calls = function_calls();
+    assert_eq!(calls.len(), 2);
+    assert_eq!(calls[0]["method_name"], "ft_transfer");
+    assert_eq!(calls[0]["deposit"], 1);
+    assert_eq!(calls[0]["gas"], 10);
+    assert_eq!(calls[1]["method_name"], "ft_total_supply");
+    assert_eq!(calls[1]["deposit"], 0);
+    assert_eq!(calls[1]["gas"], 20);
+}
+
+#[test]
+fn test_flattened_args() {
+    test_env::setup();
+    let args = TransferArgs { receiver_id: "bob.near".to_string(), amount: U128(7) };
+    ext_flat::ext("token.near".parse().unwrap(), Gas(10)).ft_transfer(args);
+
+    let calls = function_calls();
+    assert_eq!(calls.len(), 1);
+    assert_eq!(calls[0]["args"], r#"{"receiver_id":"bob.near","amount":"7"}"#);
+}
+
+#[ext_contract(ext_borsh)]
+pub trait BorshToken {
+    #[result_serializer(borsh)]
+    fn ft_transfer(
+        &mut self,
+        #[serializer(borsh)] receiver_id: String,
+        #[serializer(borsh)] amount: u128,
+    ) -> u128;
+}
+
+#[test]
+fn test_borsh_args() {
+    test_env::setup();
+    ext_borsh::ext("token.near".parse().unwrap(), Gas(10)).ft_transfer("bob.near".to_string(), 7);
+
+    let expected =
+        near_sdk::borsh::BorshSerialize::try_to_vec(&("bob.near".to_string(), 7u128)).unwrap();
+    let calls = function_calls();
+    assert_eq!(calls.len(), 1);
+    // The mocked receipts show the arguments as a string, these bytes happen to be valid UTF-8.
+    assert_eq!(calls[0]["args"], String::from_utf8(expected).unwrap());
+}
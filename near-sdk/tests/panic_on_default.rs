@@ -0,0 +1,42 @@
+//! Checks that a contract deriving `PanicOnDefault` can't be used before it's initialized.
+
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::test_utils::test_env;
+use near_sdk::{env, near_bindgen, PanicOnDefault};
+
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
+pub struct Counter {
+    value: u64,
+}
+
+#[near_bindgen]
+impl Counter {
+    #[init]
+    pub fn new(value: u64) -> Self {
+        Self { value }
+    }
+
+    pub fn get(&self) -> u64 {
+        self.value
+    }
+}
+
+/// Reads the state the way the generated wrapper of a method does before calling it.
+fn load_state() -> Counter {
+    env::state_read().unwrap_or_default()
+}
+
+#[test]
+#[should_panic(expected = "The contract is not initialized")]
+fn test_method_before_init() {
+    test_env::setup();
+    load_state().get();
+}
+
+#[test]
+fn test_method_after_init() {
+    test_env::setup();
+    env::state_write(&Counter::new(5));
+    assert_eq!(load_state().get(), 5);
+}
@@ -0,0 +1,55 @@
+//! Checks the prefix collision check of the collections, run with
+//! `cargo test --features debug-prefix-collision`.
+#![cfg(all(feature = "debug-prefix-collision", debug_assertions))]
+
+use near_sdk::collections::{nested_prefix, LookupMap, UnorderedMap, Vector};
+use near_sdk::test_utils::test_env;
+
+#[test]
+fn test_distinct_prefixes() {
+    test_env::setup();
+    let _a: UnorderedMap<u64, u64> = UnorderedMap::new(b"a");
+    let _b: UnorderedMap<u64, u64> = UnorderedMap::new(b"b");
+    let _c: LookupMap<u64, u64> = LookupMap::new(b"c");
+
+    // A new execution can reuse the prefixes.
+    test_env::setup();
+    let _a: UnorderedMap<u64, u64> = UnorderedMap::new(b"a");
+}
+
+#[test]
+#[should_panic(expected = "The storage prefix [97] is already used by another collection")]
+fn test_same_prefix() {
+    test_env::setup();
+    let _first: UnorderedMap<u64, u64> = UnorderedMap::new(b"a");
+    let _second: LookupMap<u64, u64> = LookupMap::new(b"a");
+}
+
+#[test]
+#[should_panic(expected = "The storage prefix [97] is already used by another collection")]
+fn test_same_prefix_other_element_type() {
+    test_env::setup();
+    let _first: UnorderedMap<u64, u64> = UnorderedMap::new(b"a");
+    let _second: UnorderedMap<u64, String> = UnorderedMap::new(b"a");
+}
+
+#[test]
+fn test_reopen() {
+    test_env::setup();
+    let mut outer: LookupMap<u64, LookupMap<u64, u64>> = LookupMap::new(b"o");
+    let mut inner = LookupMap::new(nested_prefix(b"o", &1u64));
+    inner.insert(&2, &3);
+    outer.insert(&1, &inner);
+    // The same collection type can be opened again under its prefix.
+    let inner: LookupMap<u64, u64> = LookupMap::new(nested_prefix(b"o", &1u64));
+    assert_eq!(inner.get(&2), Some(3));
+}
+
+#[test]
+#[should_panic(expected = "is already used by another collection")]
+fn test_prefix_of_inner_collection() {
+    test_env::setup();
+    // The keys of the map are stored in a vector under the prefix b"mk".
+    let _map: UnorderedMap<u64, u64> = UnorderedMap::new(b"m");
+    let _vector: Vector<String> = Vector::new(b"mk".to_vec());
+}
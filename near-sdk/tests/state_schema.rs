@@ -0,0 +1,47 @@
+//! Checks the state schema generated with the `schema` feature, run with
+//! `cargo test --features schema`.
+#![cfg(feature = "schema")]
+
+use near_sdk::borsh::schema::{BorshSchemaContainer, Definition, Fields};
+use near_sdk::borsh::{self, BorshDeserialize, BorshSchema, BorshSerialize};
+use near_sdk::{near_bindgen, AccountId, PanicOnDefault};
+
+#[near_bindgen(schema)]
+#[derive(BorshDeserialize, BorshSerialize, BorshSchema, PanicOnDefault)]
+pub struct Counter {
+    owner: AccountId,
+    value: u64,
+    history: Vec<u64>,
+}
+
+#[near_bindgen]
+impl Counter {
+    #[init]
+    pub fn new(owner: AccountId) -> Self {
+        Self { owner, value: 0, history: vec![] }
+    }
+}
+
+/// Without `schema` the state doesn't have to implement `BorshSchema`, even with the feature.
+#[near_bindgen]
+#[derive(BorshDeserialize, BorshSerialize, Default)]
+pub struct Unmarked {
+    value: u64,
+}
+
+#[test]
+fn test_state_schema() {
+    let raw = __state_borsh_schema();
+    assert!(!raw.is_empty());
+    let schema = BorshSchemaContainer::try_from_slice(&raw).unwrap();
+    assert_eq!(schema, Counter::schema_container());
+    assert_eq!(schema.declaration, "Counter");
+
+    let fields = match &schema.definitions["Counter"] {
+        Definition::Struct { fields: Fields::NamedFields(fields) } => fields.clone(),
+        definition => panic!("Unexpected definition {:?}", definition),
+    };
+    let fields: Vec<(&str, &str)> =
+        fields.iter().map(|(name, declaration)| (name.as_str(), declaration.as_str())).collect();
+    assert_eq!(fields, vec![("owner", "AccountId"), ("value", "u64"), ("history", "Vec<u64>")]);
+}
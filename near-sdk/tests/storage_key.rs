@@ -0,0 +1,45 @@
+//! Checks that collections created with distinct `BorshStorageKey` enum keys don't share storage.
+
+use near_sdk::borsh::{self, BorshSerialize};
+use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::test_utils::test_env;
+use near_sdk::{env, AccountId, BorshStorageKey};
+
+#[derive(BorshStorageKey, BorshSerialize)]
+enum StorageKey {
+    Balances,
+    Allowances,
+    SubAccounts { account_id: AccountId },
+}
+
+#[test]
+fn test_distinct_keys_dont_collide() {
+    test_env::setup();
+    let mut balances = UnorderedMap::new(StorageKey::Balances);
+    let mut allowances = UnorderedMap::new(StorageKey::Allowances);
+    balances.insert(&1u64, &10u64);
+    allowances.insert(&1u64, &20u64);
+    assert_eq!(balances.get(&1), Some(10));
+    assert_eq!(allowances.get(&1), Some(20));
+
+    // A unit variant is a single byte prefix, the position of the variant, followed by the
+    // prefix of the keys vector and the index of the first key.
+    assert!(env::storage_has_key(&[0, b'k', 0, 0, 0, 0, 0, 0, 0, 0]));
+    assert!(env::storage_has_key(&[1, b'k', 0, 0, 0, 0, 0, 0, 0, 0]));
+}
+
+#[test]
+fn test_nested_collections_dont_collide() {
+    test_env::setup();
+    let alice: AccountId = "alice.near".parse().unwrap();
+    let bob: AccountId = "bob.near".parse().unwrap();
+    let mut outer: LookupMap<AccountId, LookupMap<u64, u64>> =
+        LookupMap::new(StorageKey::Balances);
+    for (account_id, value) in [(alice.clone(), 1u64), (bob.clone(), 2)].iter() {
+        let mut inner = LookupMap::new(StorageKey::SubAccounts { account_id: account_id.clone() });
+        inner.insert(&0, value);
+        outer.insert(account_id, &inner);
+    }
+    assert_eq!(outer.get(&alice).unwrap().get(&0), Some(1));
+    assert_eq!(outer.get(&bob).unwrap().get(&0), Some(2));
+}